@@ -0,0 +1,283 @@
+use std::fmt;
+
+use crate::grid::Grid;
+
+const WORD_BITS: usize = 64;
+
+// A fixed length run of bits packed into words, bit i lives in words[i / 64] at i % 64. Bits past
+// len are always kept clear so equality and hashing can compare words directly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitRow {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitRow {
+    pub fn new(len: usize) -> BitRow {
+        BitRow { words: vec![0; len.div_ceil(WORD_BITS)], len }
+    }
+
+    pub fn from_bools<I: IntoIterator<Item = bool>>(bits: I) -> BitRow {
+        let bits: Vec<bool> = bits.into_iter().collect();
+        let mut row = BitRow::new(bits.len());
+        for (i, bit) in bits.into_iter().enumerate() {
+            row.set(i, bit);
+        }
+        row
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.len, "Bit {} out of range for length {}", i, self.len);
+        self.words[i / WORD_BITS] >> (i % WORD_BITS) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len, "Bit {} out of range for length {}", i, self.len);
+        let mask = 1 << (i % WORD_BITS);
+        if value {
+            self.words[i / WORD_BITS] |= mask;
+        } else {
+            self.words[i / WORD_BITS] &= !mask;
+        }
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn hamming_distance(&self, other: &BitRow) -> usize {
+        assert_eq!(self.len, other.len, "Rows must have the same length");
+        self.words.iter().zip(other.words.iter()).map(|(a, b)| (a ^ b).count_ones() as usize).sum()
+    }
+
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).filter(|i| self.get(*i))
+    }
+
+    // Moves every bit towards higher indices, bits pushed past the end are dropped
+    pub fn shifted_up(&self, amount: usize) -> BitRow {
+        let mut result = BitRow::new(self.len);
+        let (word_shift, bit_shift) = (amount / WORD_BITS, amount % WORD_BITS);
+        for i in (word_shift..self.words.len()).rev() {
+            let mut word = self.words[i - word_shift] << bit_shift;
+            if bit_shift > 0 && i > word_shift {
+                word |= self.words[i - word_shift - 1] >> (WORD_BITS - bit_shift);
+            }
+            result.words[i] = word;
+        }
+        result.clear_padding();
+        result
+    }
+
+    // Moves every bit towards index zero, bits pushed below zero are dropped
+    pub fn shifted_down(&self, amount: usize) -> BitRow {
+        let mut result = BitRow::new(self.len);
+        let (word_shift, bit_shift) = (amount / WORD_BITS, amount % WORD_BITS);
+        for i in 0..self.words.len().saturating_sub(word_shift) {
+            let mut word = self.words[i + word_shift] >> bit_shift;
+            if bit_shift > 0 && i + word_shift + 1 < self.words.len() {
+                word |= self.words[i + word_shift + 1] << (WORD_BITS - bit_shift);
+            }
+            result.words[i] = word;
+        }
+        result
+    }
+
+    fn clear_padding(&mut self) {
+        let used = self.len % WORD_BITS;
+        if used > 0 {
+            *self.words.last_mut().unwrap() &= (1 << used) - 1;
+        }
+    }
+}
+
+impl fmt::Display for BitRow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.len {
+            write!(f, "{}", if self.get(i) { '#' } else { '.' })?;
+        }
+        Ok(())
+    }
+}
+
+// Keeps both a row-major and a column-major copy so either axis can be compared word by word
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitGrid {
+    rows: Vec<BitRow>,
+    columns: Vec<BitRow>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> BitGrid {
+        BitGrid {
+            rows: vec![BitRow::new(width); height],
+            columns: vec![BitRow::new(height); width],
+        }
+    }
+
+    pub fn from_grid<T>(grid: &Grid<T>, is_set: impl Fn(&T) -> bool) -> BitGrid {
+        let mut result = BitGrid::new(grid.width(), grid.height());
+        for ((x, y), cell) in grid.iter() {
+            if is_set(cell) {
+                result.set(x, y, true);
+            }
+        }
+        result
+    }
+
+    pub fn width(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.rows[y].get(x)
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        self.rows[y].set(x, value);
+        self.columns[x].set(y, value);
+    }
+
+    pub fn row(&self, y: usize) -> &BitRow {
+        &self.rows[y]
+    }
+
+    pub fn rows(&self) -> &[BitRow] {
+        &self.rows
+    }
+
+    pub fn column(&self, x: usize) -> &BitRow {
+        &self.columns[x]
+    }
+
+    pub fn columns(&self) -> &[BitRow] {
+        &self.columns
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.rows.iter().map(BitRow::count_ones).sum()
+    }
+
+    pub fn row_distance(&self, a: usize, b: usize) -> usize {
+        self.rows[a].hamming_distance(&self.rows[b])
+    }
+
+    pub fn column_distance(&self, a: usize, b: usize) -> usize {
+        self.columns[a].hamming_distance(&self.columns[b])
+    }
+
+    pub fn transposed(&self) -> BitGrid {
+        BitGrid { rows: self.columns.clone(), columns: self.rows.clone() }
+    }
+
+    // Moves every set cell by (dx, dy), cells that leave the grid are dropped
+    pub fn shifted(&self, dx: isize, dy: isize) -> BitGrid {
+        let shift = |row: &BitRow, amount: isize| if amount >= 0 {
+            row.shifted_up(amount as usize)
+        } else {
+            row.shifted_down(amount.unsigned_abs())
+        };
+        let mut rows = vec![BitRow::new(self.width()); self.height()];
+        for (y, row) in self.rows.iter().enumerate() {
+            let target = y as isize + dy;
+            if target >= 0 && (target as usize) < self.height() {
+                rows[target as usize] = shift(row, dx);
+            }
+        }
+        let columns = (0..self.width())
+            .map(|x| BitRow::from_bools(rows.iter().map(|r| r.get(x))))
+            .collect();
+        BitGrid { rows, columns }
+    }
+}
+
+impl fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in &self.rows {
+            writeln!(f, "{}", row)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod bitgrid_tests {
+    use super::*;
+
+    fn build_grid() -> BitGrid {
+        let grid = Grid::parse(&["#.#.", "..##", "#..."], Some).unwrap();
+        BitGrid::from_grid(&grid, |c| *c == '#')
+    }
+
+    #[test]
+    fn from_grid_fills_rows_and_columns() {
+        let grid = build_grid();
+        assert_eq!((grid.width(), grid.height()), (4, 3));
+        assert!(grid.get(2, 1));
+        assert!(!grid.get(1, 1));
+        assert_eq!(grid.row(1).to_string(), "..##");
+        assert_eq!(grid.column(0).to_string(), "#.#");
+        assert_eq!(grid.count_ones(), 5);
+    }
+
+    #[test]
+    fn hamming_distance_between_rows_and_columns() {
+        let grid = build_grid();
+        assert_eq!(grid.row_distance(0, 1), 2);
+        assert_eq!(grid.row_distance(0, 2), 1);
+        assert_eq!(grid.column_distance(0, 2), 2);
+    }
+
+    #[test]
+    fn rows_wider_than_a_word() {
+        let mut row = BitRow::new(200);
+        row.set(0, true);
+        row.set(64, true);
+        row.set(199, true);
+        let mut other = row.clone();
+        assert_eq!(row, other);
+        other.set(150, true);
+        assert_ne!(row, other);
+        assert_eq!(row.hamming_distance(&other), 1);
+        assert_eq!(row.ones().collect::<Vec<usize>>(), vec![0, 64, 199]);
+    }
+
+    #[test]
+    fn shifts_cross_word_boundaries_and_drop_overflow() {
+        let row = BitRow::from_bools((0..130).map(|i| i == 0 || i == 63 || i == 129));
+        assert_eq!(row.shifted_up(1).ones().collect::<Vec<usize>>(), vec![1, 64]);
+        assert_eq!(row.shifted_up(70).ones().collect::<Vec<usize>>(), vec![70]);
+        assert_eq!(row.shifted_down(63).ones().collect::<Vec<usize>>(), vec![0, 66]);
+        assert_eq!(row.shifted_down(130).count_ones(), 0);
+        // Shifting back after losing a bit must not resurrect it
+        assert_eq!(row.shifted_up(1).shifted_down(1).ones().collect::<Vec<usize>>(), vec![0, 63]);
+    }
+
+    #[test]
+    fn shifted_grid_moves_cells() {
+        let grid = build_grid().shifted(1, -1);
+        assert_eq!(grid.to_string(), "...#\n.#..\n....\n");
+        assert_eq!(grid.column(3).to_string(), "#..");
+    }
+
+    #[test]
+    fn transposed_swaps_axes() {
+        let grid = build_grid();
+        let transposed = grid.transposed();
+        assert_eq!((transposed.width(), transposed.height()), (3, 4));
+        assert_eq!(transposed.row(0), grid.column(0));
+        assert_eq!(transposed.transposed(), grid);
+    }
+}
@@ -0,0 +1,314 @@
+use std::fmt;
+
+const WORD_BITS: usize = 64;
+
+// Growable set of small non-negative integers. Trailing zero words are trimmed after every
+// change, so two sets with the same members compare and hash equal whatever their history.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> BitSet {
+        BitSet { words: Vec::new() }
+    }
+
+    pub fn with_capacity(bits: usize) -> BitSet {
+        BitSet { words: Vec::with_capacity(bits.div_ceil(WORD_BITS)) }
+    }
+
+    fn trim(&mut self) {
+        while self.words.last() == Some(&0) {
+            self.words.pop();
+        }
+    }
+
+    // Returns true if the value was not already present
+    pub fn insert(&mut self, value: usize) -> bool {
+        let (word, bit) = (value / WORD_BITS, value % WORD_BITS);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let was_present = self.words[word] >> bit & 1 == 1;
+        self.words[word] |= 1 << bit;
+        !was_present
+    }
+
+    // Returns true if the value was present
+    pub fn remove(&mut self, value: usize) -> bool {
+        if !self.contains(value) {
+            return false;
+        }
+        self.words[value / WORD_BITS] &= !(1 << (value % WORD_BITS));
+        self.trim();
+        true
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.words.get(value / WORD_BITS).is_some_and(|w| w >> (value % WORD_BITS) & 1 == 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    pub fn union_with(&mut self, other: &BitSet) {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (a, b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a |= b;
+        }
+    }
+
+    pub fn intersect_with(&mut self, other: &BitSet) {
+        self.words.truncate(other.words.len());
+        for (a, b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a &= b;
+        }
+        self.trim();
+    }
+
+    pub fn difference_with(&mut self, other: &BitSet) {
+        for (a, b) in self.words.iter_mut().zip(other.words.iter()) {
+            *a &= !b;
+        }
+        self.trim();
+    }
+
+    pub fn union(&self, other: &BitSet) -> BitSet {
+        let mut result = self.clone();
+        result.union_with(other);
+        result
+    }
+
+    pub fn intersection(&self, other: &BitSet) -> BitSet {
+        let mut result = self.clone();
+        result.intersect_with(other);
+        result
+    }
+
+    pub fn difference(&self, other: &BitSet) -> BitSet {
+        let mut result = self.clone();
+        result.difference_with(other);
+        result
+    }
+
+    pub fn is_subset(&self, other: &BitSet) -> bool {
+        self.words.len() <= other.words.len()
+            && self.words.iter().zip(other.words.iter()).all(|(a, b)| a & !b == 0)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        iter_words(&self.words)
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(values: I) -> BitSet {
+        let mut set = BitSet::new();
+        for value in values {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+// Walks the set bits of each word lowest first by repeatedly clearing the lowest one
+fn iter_words(words: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    words.iter().enumerate().flat_map(|(i, word)| {
+        let mut remaining = *word;
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            let bit = remaining.trailing_zeros() as usize;
+            remaining &= remaining - 1;
+            Some(i * WORD_BITS + bit)
+        })
+    })
+}
+
+// Fixed capacity set of values below 64 * W, small enough to copy and use as a map key freely
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SmallBitSet<const W: usize> {
+    words: [u64; W],
+}
+
+pub type BitSet64 = SmallBitSet<1>;
+pub type BitSet128 = SmallBitSet<2>;
+pub type BitSet256 = SmallBitSet<4>;
+
+impl<const W: usize> Default for SmallBitSet<W> {
+    fn default() -> Self {
+        SmallBitSet { words: [0; W] }
+    }
+}
+
+impl<const W: usize> SmallBitSet<W> {
+    pub const CAPACITY: usize = W * WORD_BITS;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Returns true if the value was not already present, panics past the fixed capacity
+    pub fn insert(&mut self, value: usize) -> bool {
+        assert!(value < Self::CAPACITY, "{} does not fit in a set of {} bits", value, Self::CAPACITY);
+        let was_present = self.contains(value);
+        self.words[value / WORD_BITS] |= 1 << (value % WORD_BITS);
+        !was_present
+    }
+
+    pub fn remove(&mut self, value: usize) -> bool {
+        let was_present = self.contains(value);
+        if was_present {
+            self.words[value / WORD_BITS] &= !(1 << (value % WORD_BITS));
+        }
+        was_present
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        value < Self::CAPACITY && self.words[value / WORD_BITS] >> (value % WORD_BITS) & 1 == 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|w| *w == 0)
+    }
+
+    fn combine(&self, other: &Self, op: impl Fn(u64, u64) -> u64) -> Self {
+        let mut words = [0; W];
+        for (i, word) in words.iter_mut().enumerate() {
+            *word = op(self.words[i], other.words[i]);
+        }
+        SmallBitSet { words }
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a | b)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & b)
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        iter_words(&self.words)
+    }
+}
+
+impl<const W: usize> FromIterator<usize> for SmallBitSet<W> {
+    fn from_iter<I: IntoIterator<Item = usize>>(values: I) -> Self {
+        let mut set = Self::new();
+        for value in values {
+            set.insert(value);
+        }
+        set
+    }
+}
+
+impl<const W: usize> fmt::Debug for SmallBitSet<W> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod bitset_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn insert_contains_and_remove() {
+        let mut set = BitSet::new();
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(200));
+        assert!(set.contains(200));
+        assert!(!set.contains(199));
+        assert!(!set.contains(100_000));
+        assert_eq!(set.len(), 2);
+        assert!(set.remove(200));
+        assert!(!set.remove(200));
+        assert_eq!(set.iter().collect::<Vec<usize>>(), vec![3]);
+    }
+
+    #[test]
+    fn equal_members_compare_equal_after_shrinking() {
+        let mut grown: BitSet = [1, 500].into_iter().collect();
+        grown.remove(500);
+        let small: BitSet = [1].into_iter().collect();
+        assert_eq!(grown, small);
+        let mut counts = HashMap::new();
+        *counts.entry(grown).or_insert(0) += 1;
+        *counts.entry(small).or_insert(0) += 1;
+        assert_eq!(counts.len(), 1);
+    }
+
+    #[test]
+    fn set_operations() {
+        let a: BitSet = [1, 2, 3, 130].into_iter().collect();
+        let b: BitSet = [2, 3, 4].into_iter().collect();
+        assert_eq!(a.union(&b).iter().collect::<Vec<usize>>(), vec![1, 2, 3, 4, 130]);
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<usize>>(), vec![2, 3]);
+        assert_eq!(a.difference(&b).iter().collect::<Vec<usize>>(), vec![1, 130]);
+        assert_eq!(b.difference(&a).iter().collect::<Vec<usize>>(), vec![4]);
+        assert!(a.intersection(&b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+        assert!(BitSet::new().is_subset(&b));
+    }
+
+    #[test]
+    fn small_set_operations() {
+        let a: BitSet128 = [0, 64, 127].into_iter().collect();
+        let b: BitSet128 = [64, 100].into_iter().collect();
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<usize>>(), vec![64]);
+        assert_eq!(a.union(&b).len(), 4);
+        assert_eq!(a.difference(&b).iter().collect::<Vec<usize>>(), vec![0, 127]);
+        assert!(!a.contains(500));
+        assert_eq!(BitSet256::CAPACITY, 256);
+        assert_eq!(format!("{:?}", b), "{64, 100}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn small_set_when_value_too_large_panics() {
+        BitSet64::new().insert(64);
+    }
+
+    #[test]
+    fn small_sets_work_as_map_keys() {
+        let mut visited: HashMap<(BitSet64, usize), u32> = HashMap::new();
+        let mask: BitSet64 = [1, 5].into_iter().collect();
+        visited.insert((mask, 5), 10);
+        let same: BitSet64 = [5, 1].into_iter().collect();
+        assert_eq!(visited.get(&(same, 5)), Some(&10));
+    }
+}
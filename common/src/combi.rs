@@ -0,0 +1,217 @@
+use num_bigint::BigInt;
+
+use crate::math::gcd;
+
+// Every unordered pair (items[i], items[j]) with i < j, in index order
+pub fn pairs<T>(items: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    items.iter().enumerate()
+        .flat_map(move |(i, a)| items[i + 1..].iter().map(move |b| (a, b)))
+}
+
+// Every ordered pair of distinct positions, so both (a, b) and (b, a)
+pub fn ordered_pairs<T>(items: &[T]) -> impl Iterator<Item = (&T, &T)> {
+    items.iter().enumerate().flat_map(move |(i, a)| {
+        items.iter().enumerate().filter(move |(j, _)| *j != i).map(move |(_, b)| (a, b))
+    })
+}
+
+pub fn combinations<T>(items: &[T], k: usize) -> Combinations<'_, T> {
+    Combinations { items, indices: (0..k).collect(), done: k > items.len() }
+}
+
+// The k element subsets in lexicographic order of their positions
+#[derive(Debug, Clone)]
+pub struct Combinations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, T> Iterator for Combinations<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.indices.iter().map(|i| &self.items[*i]).collect();
+        let (n, k) = (self.items.len(), self.indices.len());
+        // Bump the rightmost index that still has room, then pack the rest right after it
+        match (0..k).rev().find(|i| self.indices[*i] < n - k + i) {
+            Some(i) => {
+                self.indices[i] += 1;
+                for j in i + 1..k {
+                    self.indices[j] = self.indices[j - 1] + 1;
+                }
+            }
+            None => self.done = true,
+        }
+        Some(result)
+    }
+}
+
+pub fn permutations<T>(items: &[T]) -> Permutations<'_, T> {
+    Permutations { items, indices: (0..items.len()).collect(), done: false }
+}
+
+// Every ordering of the items, in lexicographic order of their positions
+#[derive(Debug, Clone)]
+pub struct Permutations<'a, T> {
+    items: &'a [T],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, T> Iterator for Permutations<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.indices.iter().map(|i| &self.items[*i]).collect();
+        self.done = !next_permutation(&mut self.indices);
+        Some(result)
+    }
+}
+
+// Rearranges into the next larger ordering, returns false once the values are in descending order
+pub fn next_permutation<T: Ord>(values: &mut [T]) -> bool {
+    let Some(pivot) = (1..values.len()).rev().find(|i| values[i - 1] < values[*i]).map(|i| i - 1) else {
+        return false;
+    };
+    let successor = (pivot + 1..values.len()).rev().find(|i| values[*i] > values[pivot]).unwrap();
+    values.swap(pivot, successor);
+    values[pivot + 1..].reverse();
+    true
+}
+
+pub fn cartesian_product<T>(lists: &[Vec<T>]) -> CartesianProduct<'_, T> {
+    CartesianProduct { lists, indices: vec![0; lists.len()], done: lists.iter().any(Vec::is_empty) }
+}
+
+// One item from each list, the last list varying fastest
+#[derive(Debug, Clone)]
+pub struct CartesianProduct<'a, T> {
+    lists: &'a [Vec<T>],
+    indices: Vec<usize>,
+    done: bool,
+}
+
+impl<'a, T> Iterator for CartesianProduct<'a, T> {
+    type Item = Vec<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.indices.iter().zip(self.lists).map(|(i, list)| &list[*i]).collect();
+        // Count up like an odometer
+        self.done = true;
+        for position in (0..self.lists.len()).rev() {
+            self.indices[position] += 1;
+            if self.indices[position] < self.lists[position].len() {
+                self.done = false;
+                break;
+            }
+            self.indices[position] = 0;
+        }
+        Some(result)
+    }
+}
+
+// n choose k, None when the result does not fit. Each step divides out the common factor first
+// so intermediate products only overflow when the result itself is close to doing so.
+pub fn binomial(n: u64, k: u64) -> Option<u128> {
+    if k > n {
+        return Some(0);
+    }
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k as u128 {
+        let (numerator, denominator) = (n as u128 - i, i + 1);
+        let common = gcd(result, denominator);
+        result = (result / common).checked_mul(numerator / (denominator / common))?;
+    }
+    Some(result)
+}
+
+pub fn binomial_big(n: u64, k: u64) -> BigInt {
+    if k > n {
+        return BigInt::from(0);
+    }
+    let k = k.min(n - k);
+    let mut result = BigInt::from(1);
+    for i in 0..k {
+        result = result * (n - i) / (i + 1);
+    }
+    result
+}
+
+#[cfg(test)]
+mod combi_tests {
+    use super::*;
+
+    #[test]
+    fn pairs_visit_each_unordered_pair_once() {
+        let items = [1, 2, 3, 4];
+        let found: Vec<(i32, i32)> = pairs(&items).map(|(a, b)| (*a, *b)).collect();
+        assert_eq!(found, vec![(1, 2), (1, 3), (1, 4), (2, 3), (2, 4), (3, 4)]);
+        assert_eq!(pairs(&[1]).count(), 0);
+        assert_eq!(ordered_pairs(&items).count(), 12);
+    }
+
+    #[test]
+    fn combinations_in_lexicographic_order() {
+        let found: Vec<String> = combinations(&['a', 'b', 'c', 'd'], 2)
+            .map(|c| c.into_iter().collect())
+            .collect();
+        assert_eq!(found, vec!["ab", "ac", "ad", "bc", "bd", "cd"]);
+        assert_eq!(combinations(&[1, 2, 3], 0).collect::<Vec<Vec<&i32>>>(), vec![Vec::<&i32>::new()]);
+        assert_eq!(combinations(&[1, 2, 3], 4).count(), 0);
+        assert_eq!(combinations(&(0..10).collect::<Vec<u32>>(), 4).count() as u128, binomial(10, 4).unwrap());
+    }
+
+    #[test]
+    fn permutations_in_lexicographic_order() {
+        let found: Vec<String> = permutations(&['x', 'y', 'z'])
+            .map(|p| p.into_iter().collect())
+            .collect();
+        assert_eq!(found, vec!["xyz", "xzy", "yxz", "yzx", "zxy", "zyx"]);
+        assert_eq!(permutations::<u8>(&[]).count(), 1);
+        assert_eq!(permutations(&[0; 5]).count(), 120);
+    }
+
+    #[test]
+    fn next_permutation_skips_duplicates() {
+        let mut values = vec![1, 1, 2];
+        let mut count = 1;
+        while next_permutation(&mut values) {
+            count += 1;
+        }
+        assert_eq!(count, 3);
+        assert_eq!(values, vec![2, 1, 1]);
+    }
+
+    #[test]
+    fn cartesian_product_counts_like_an_odometer() {
+        let lists = vec![vec![0, 1], vec![5, 6, 7]];
+        let found: Vec<Vec<i32>> = cartesian_product(&lists)
+            .map(|p| p.into_iter().copied().collect())
+            .collect();
+        assert_eq!(found, vec![vec![0, 5], vec![0, 6], vec![0, 7], vec![1, 5], vec![1, 6], vec![1, 7]]);
+        assert_eq!(cartesian_product(&[vec![1], Vec::new()]).count(), 0);
+        assert_eq!(cartesian_product::<i32>(&[]).count(), 1);
+    }
+
+    #[test]
+    fn binomial_checks_overflow() {
+        assert_eq!(binomial(5, 2), Some(10));
+        assert_eq!(binomial(5, 7), Some(0));
+        assert_eq!(binomial(0, 0), Some(1));
+        assert_eq!(binomial(130, 65), Some(95067625827960698145584333020095113100));
+        assert_eq!(binomial(200, 100), None);
+        assert_eq!(binomial_big(200, 100).to_string(), "90548514656103281165404177077484163874504589675413336841320");
+        assert_eq!(binomial_big(130, 65), BigInt::from(95067625827960698145584333020095113100u128));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::range::Range;
+
+// Maps the distinct values along one axis onto a dense index space. Every distinct value gets
+// its own cell at an even index, and the (possibly empty) gap before the next value gets the
+// odd index in between, so no real coordinate is lost when compressing.
+#[derive(Debug, Clone)]
+pub struct CompressedAxis {
+    values: Vec<i64>,
+}
+
+impl CompressedAxis {
+    pub fn new<I: IntoIterator<Item = i64>>(values: I) -> CompressedAxis {
+        let mut values: Vec<i64> = values.into_iter().collect();
+        values.sort();
+        values.dedup();
+        CompressedAxis { values }
+    }
+
+    pub fn values(&self) -> &[i64] {
+        &self.values
+    }
+
+    pub fn cell_count(&self) -> usize {
+        (self.values.len() * 2).saturating_sub(1)
+    }
+
+    pub fn compress(&self, value: i64) -> Option<usize> {
+        self.values.binary_search(&value).ok().map(|i| i * 2)
+    }
+
+    // Like compress, but values that fall between known values map to their gap cell
+    pub fn compress_nearest(&self, value: i64) -> Option<usize> {
+        match self.values.binary_search(&value) {
+            Ok(i) => Some(i * 2),
+            Err(i) if i > 0 && i < self.values.len() => Some(i * 2 - 1),
+            Err(_) => None,
+        }
+    }
+
+    pub fn decompress(&self, index: usize) -> Range {
+        if index.is_multiple_of(2) {
+            let value = self.values[index / 2];
+            Range::new(value, value)
+        } else {
+            Range::new(self.values[index / 2] + 1, self.values[index / 2 + 1] - 1)
+        }
+    }
+
+    pub fn span(&self, index: usize) -> i64 {
+        self.decompress(index).len()
+    }
+
+    // Number of distinct values strictly less than the given value
+    pub fn rank(&self, value: i64) -> usize {
+        self.values.partition_point(|v| *v < value)
+    }
+
+    // Number of integers strictly between a and b that are not values on this axis
+    // The count can pass i64::MAX when the ends are far apart, so it is a u64
+    pub fn missing_between(&self, a: i64, b: i64) -> u64 {
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        let gap = high.abs_diff(low);
+        if gap < 2 {
+            return 0;
+        }
+        let present = self.rank(high) - self.rank(low + 1);
+        (gap - 1) - present as u64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CoordinateCompression {
+    pub x: CompressedAxis,
+    pub y: CompressedAxis,
+}
+
+impl CoordinateCompression {
+    pub fn from_points(points: &[(i64, i64)]) -> CoordinateCompression {
+        CoordinateCompression {
+            x: CompressedAxis::new(points.iter().map(|p| p.0)),
+            y: CompressedAxis::new(points.iter().map(|p| p.1)),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.x.cell_count()
+    }
+
+    pub fn height(&self) -> usize {
+        self.y.cell_count()
+    }
+
+    pub fn compress(&self, point: (i64, i64)) -> Option<(usize, usize)> {
+        Some((self.x.compress(point.0)?, self.y.compress(point.1)?))
+    }
+
+    pub fn decompress(&self, cell: (usize, usize)) -> (Range, Range) {
+        (self.x.decompress(cell.0), self.y.decompress(cell.1))
+    }
+
+    pub fn cell_area(&self, cell: (usize, usize)) -> i64 {
+        self.x.span(cell.0) * self.y.span(cell.1)
+    }
+}
+
+#[cfg(test)]
+mod compressed_axis_tests {
+    use super::*;
+
+    #[test]
+    fn new_sorts_and_dedups() {
+        let axis = CompressedAxis::new(vec![10, 1, 1_000_000, 10]);
+        assert_eq!(axis.values(), &[1, 10, 1_000_000]);
+        assert_eq!(axis.cell_count(), 5);
+    }
+
+    #[test]
+    fn compress_maps_values_to_even_cells() {
+        let axis = CompressedAxis::new(vec![10, 1, 1_000_000]);
+        assert_eq!(axis.compress(1), Some(0));
+        assert_eq!(axis.compress(10), Some(2));
+        assert_eq!(axis.compress(1_000_000), Some(4));
+        assert_eq!(axis.compress(5), None);
+    }
+
+    #[test]
+    fn compress_nearest_maps_gaps_to_odd_cells() {
+        let axis = CompressedAxis::new(vec![10, 1, 1_000_000]);
+        assert_eq!(axis.compress_nearest(5), Some(1));
+        assert_eq!(axis.compress_nearest(500), Some(3));
+        assert_eq!(axis.compress_nearest(0), None);
+        assert_eq!(axis.compress_nearest(1_000_001), None);
+    }
+
+    #[test]
+    fn decompress_returns_real_span() {
+        let axis = CompressedAxis::new(vec![1, 2, 10]);
+        assert_eq!(axis.decompress(0), Range::new(1, 1));
+        assert!(axis.decompress(1).is_empty());
+        assert_eq!(axis.decompress(3), Range::new(3, 9));
+        assert_eq!(axis.span(3), 7);
+        assert_eq!(axis.span(4), 1);
+    }
+
+    #[test]
+    fn missing_between_counts_absent_values() {
+        let axis = CompressedAxis::new(vec![0, 1, 4, 7, 8]);
+        assert_eq!(axis.missing_between(0, 8), 4);
+        assert_eq!(axis.missing_between(8, 0), 4);
+        assert_eq!(axis.missing_between(1, 4), 2);
+        assert_eq!(axis.missing_between(7, 8), 0);
+        assert_eq!(axis.missing_between(4, 4), 0);
+    }
+
+    #[test]
+    fn missing_between_at_extremes_does_not_overflow() {
+        let axis = CompressedAxis::new(vec![i64::MIN, -1, 0, i64::MAX]);
+        assert_eq!(axis.missing_between(i64::MIN, i64::MAX), u64::MAX - 3);
+        assert_eq!(axis.missing_between(i64::MAX, i64::MIN), u64::MAX - 3);
+        assert_eq!(axis.missing_between(i64::MIN, 0), i64::MAX as u64 - 1);
+        assert_eq!(CompressedAxis::new(vec![]).missing_between(i64::MIN, i64::MAX), u64::MAX - 1);
+    }
+}
+
+#[cfg(test)]
+mod coordinate_compression_tests {
+    use super::*;
+
+    #[test]
+    fn compress_and_decompress_points() {
+        let compression = CoordinateCompression::from_points(&[(0, 0), (1_000_000, 5), (3, 5)]);
+        assert_eq!(compression.width(), 5);
+        assert_eq!(compression.height(), 3);
+        assert_eq!(compression.compress((3, 5)), Some((2, 2)));
+        assert_eq!(compression.compress((3, 4)), None);
+        assert_eq!(compression.decompress((3, 1)), (Range::new(4, 999_999), Range::new(1, 4)));
+        assert_eq!(compression.cell_area((3, 1)), 999_996 * 4);
+    }
+}
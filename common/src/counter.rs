@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Multiset of keys, keys whose count drops to zero are removed entirely
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<K: Hash + Eq> {
+    counts: HashMap<K, usize>,
+}
+
+impl<K: Hash + Eq> Default for Counter<K> {
+    fn default() -> Self {
+        Counter { counts: HashMap::new() }
+    }
+}
+
+impl<K: Hash + Eq> Counter<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, key: K) {
+        self.add_many(key, 1);
+    }
+
+    pub fn add_many(&mut self, key: K, count: usize) {
+        if count > 0 {
+            *self.counts.entry(key).or_insert(0) += count;
+        }
+    }
+
+    // Removes one occurrence, returns false if there was none
+    pub fn remove(&mut self, key: &K) -> bool {
+        match self.counts.get_mut(key) {
+            Some(count) if *count > 1 => *count -= 1,
+            Some(_) => {
+                self.counts.remove(key);
+            }
+            None => return false,
+        }
+        true
+    }
+
+    // Removes every occurrence and returns how many there were
+    pub fn take(&mut self, key: &K) -> usize {
+        self.counts.remove(key).unwrap_or(0)
+    }
+
+    pub fn get(&self, key: &K) -> usize {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    // Number of distinct keys
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, usize)> {
+        self.counts.iter().map(|(key, count)| (key, *count))
+    }
+
+    // The counts alone, largest first, so a full house is [3, 2] whatever its ranks
+    pub fn signature(&self) -> Vec<usize> {
+        let mut counts: Vec<usize> = self.counts.values().copied().collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        counts
+    }
+
+    pub fn merge(&mut self, other: &Counter<K>) where K: Clone {
+        for (key, count) in other.iter() {
+            self.add_many(key.clone(), count);
+        }
+    }
+
+    // Keeps the larger count of each key
+    pub fn max_merge(&mut self, other: &Counter<K>) where K: Clone {
+        for (key, count) in other.iter() {
+            let current = self.counts.entry(key.clone()).or_insert(0);
+            *current = (*current).max(count);
+        }
+    }
+
+    // Keys by descending count, ties broken by ascending key so the order is stable
+    pub fn most_common(&self) -> Vec<(&K, usize)> where K: Ord {
+        let mut entries: Vec<(&K, usize)> = self.iter().collect();
+        entries.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        entries
+    }
+}
+
+impl<K: Hash + Eq> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(keys: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(keys);
+        counter
+    }
+}
+
+impl<K: Hash + Eq> Extend<K> for Counter<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, keys: I) {
+        for key in keys {
+            self.add(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod counter_tests {
+    use super::*;
+
+    #[test]
+    fn counts_and_removes() {
+        let mut counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!((counter.len(), counter.total()), (5, 11));
+        assert!(counter.remove(&'c'));
+        assert!(!counter.remove(&'c'));
+        assert_eq!(counter.len(), 4);
+        assert_eq!(counter.take(&'b'), 2);
+        assert_eq!(counter.take(&'b'), 0);
+        assert_eq!(counter.total(), 8);
+    }
+
+    #[test]
+    fn most_common_breaks_ties_by_key() {
+        let counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter.most_common(), vec![(&'a', 5), (&'b', 2), (&'r', 2), (&'c', 1), (&'d', 1)]);
+    }
+
+    #[test]
+    fn signature_ignores_which_keys() {
+        let full_house: Counter<char> = "KTKTK".chars().collect();
+        assert_eq!(full_house.signature(), vec![3, 2]);
+        let two_pairs: Counter<char> = "23432".chars().collect();
+        assert_eq!(two_pairs.signature(), vec![2, 2, 1]);
+        assert!(Counter::<char>::new().signature().is_empty());
+    }
+
+    #[test]
+    fn merge_sums_and_max_merge_keeps_largest() {
+        let mut first: Counter<&str> = Counter::new();
+        first.add_many("red", 4);
+        first.add_many("blue", 3);
+        let mut second = Counter::new();
+        second.add_many("red", 1);
+        second.add_many("green", 2);
+
+        let mut merged = first.clone();
+        merged.merge(&second);
+        assert_eq!((merged.get(&"red"), merged.get(&"green"), merged.total()), (5, 2, 10));
+
+        first.max_merge(&second);
+        assert_eq!((first.get(&"red"), first.get(&"blue"), first.get(&"green")), (4, 3, 2));
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+pub trait Simulation {
+    fn step(&self) -> Self;
+}
+
+// Every state visited before the first repeat. States from `start` onwards loop forever with
+// period `length`, so any later step maps back into the recorded history.
+#[derive(Debug, Clone)]
+pub struct Cycle<S> {
+    pub start: usize,
+    pub length: usize,
+    history: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    pub fn index_after(&self, steps: u64) -> usize {
+        if steps < self.history.len() as u64 {
+            return steps as usize;
+        }
+        let offset = (steps - self.start as u64) % self.length as u64;
+        self.start + offset as usize
+    }
+
+    pub fn state_after(&self, steps: u64) -> &S {
+        &self.history[self.index_after(steps)]
+    }
+
+    pub fn history(&self) -> &[S] {
+        &self.history
+    }
+
+    pub fn cycle_states(&self) -> &[S] {
+        &self.history[self.start..]
+    }
+
+    pub fn into_state_after(mut self, steps: u64) -> S {
+        let index = self.index_after(steps);
+        self.history.swap_remove(index)
+    }
+}
+
+// Compares whole states, so the simulation must eventually revisit one or this never returns
+pub fn detect_with<S, F>(initial: S, mut step: F) -> Cycle<S>
+    where S: Hash + Eq + Clone, F: FnMut(&S) -> S {
+    let mut seen: HashMap<S, usize> = HashMap::new();
+    let mut history: Vec<S> = Vec::new();
+    let mut state = initial;
+    loop {
+        if let Some(start) = seen.get(&state) {
+            return Cycle { start: *start, length: history.len() - start, history };
+        }
+        seen.insert(state.clone(), history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+// Buckets states by a cheap key, then confirms a repeat with full equality so a lossy key can
+// never produce a false cycle
+pub fn detect_by_key_with<S, K, F, G>(initial: S, mut step: F, mut key: G) -> Cycle<S>
+    where S: PartialEq, K: Hash + Eq, F: FnMut(&S) -> S, G: FnMut(&S) -> K {
+    let mut seen: HashMap<K, Vec<usize>> = HashMap::new();
+    let mut history: Vec<S> = Vec::new();
+    let mut state = initial;
+    loop {
+        let bucket = seen.entry(key(&state)).or_default();
+        if let Some(start) = bucket.iter().find(|i| history[**i] == state) {
+            let start = *start;
+            return Cycle { start, length: history.len() - start, history };
+        }
+        bucket.push(history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+pub fn detect<S: Simulation + Hash + Eq + Clone>(initial: S) -> Cycle<S> {
+    detect_with(initial, S::step)
+}
+
+pub fn detect_by_key<S, K, G>(initial: S, key: G) -> Cycle<S>
+    where S: Simulation + PartialEq, K: Hash + Eq, G: FnMut(&S) -> K {
+    detect_by_key_with(initial, S::step, key)
+}
+
+pub fn state_after<S: Simulation + Hash + Eq + Clone>(initial: S, steps: u64) -> S {
+    detect(initial).into_state_after(steps)
+}
+
+#[cfg(test)]
+mod cycle_tests {
+    use super::*;
+
+    // x -> x^2 + 1 mod 255 from 3 runs 3, 10, 101, 2, 5, 26, 167, 95, 101, ...
+    #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+    struct Square(u64);
+
+    impl Simulation for Square {
+        fn step(&self) -> Self {
+            Square((self.0 * self.0 + 1) % 255)
+        }
+    }
+
+    fn brute_force(initial: Square, steps: u64) -> Square {
+        let mut state = initial;
+        for _ in 0..steps {
+            state = state.step();
+        }
+        state
+    }
+
+    #[test]
+    fn detect_finds_start_and_length() {
+        let cycle = detect(Square(3));
+        assert_eq!(cycle.start, 2);
+        assert_eq!(cycle.length, 6);
+        assert_eq!(cycle.cycle_states()[0], Square(101));
+        assert_eq!(cycle.history().len(), 8);
+    }
+
+    #[test]
+    fn state_after_matches_brute_force() {
+        let cycle = detect(Square(3));
+        for steps in 0..50 {
+            assert_eq!(*cycle.state_after(steps), brute_force(Square(3), steps));
+        }
+    }
+
+    #[test]
+    fn state_after_skips_huge_step_counts() {
+        let expected = brute_force(Square(3), 2 + (1_000_000_000 - 2) % 6);
+        assert_eq!(state_after(Square(3), 1_000_000_000), expected);
+    }
+
+    #[test]
+    fn detect_by_key_survives_key_collisions() {
+        // Every state shares the same key, so only the equality check can find the cycle
+        let cycle = detect_by_key(Square(3), |_| 0);
+        assert_eq!((cycle.start, cycle.length), (2, 6));
+    }
+
+    #[test]
+    fn detect_with_closure_for_external_context() {
+        let table = [1, 2, 3, 1];
+        let cycle = detect_with(0usize, |i| table[*i]);
+        assert_eq!((cycle.start, cycle.length), (1, 3));
+        assert_eq!(*cycle.state_after(10), 1);
+    }
+}
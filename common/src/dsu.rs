@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Union-find over dense ids 0..len, with path compression and union by size
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    component_count: usize,
+}
+
+impl DisjointSet {
+    pub fn new(len: usize) -> DisjointSet {
+        DisjointSet { parent: (0..len).collect(), size: vec![1; len], component_count: len }
+    }
+
+    // Adds a new singleton and returns its id
+    pub fn add(&mut self) -> usize {
+        let id = self.parent.len();
+        self.parent.push(id);
+        self.size.push(1);
+        self.component_count += 1;
+        id
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&mut self, id: usize) -> usize {
+        let mut root = id;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        let mut current = id;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    // Returns false if the two were already in the same component
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        self.component_count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.component_count
+    }
+
+    pub fn component_size(&mut self, id: usize) -> usize {
+        let root = self.find(id);
+        self.size[root]
+    }
+
+    // Sizes of every component, largest first
+    pub fn component_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|id| self.parent[*id] == *id)
+            .map(|root| self.size[root])
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    // Members of every component, each sorted, ordered by their smallest member
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let mut groups: HashMap<usize, usize> = HashMap::new();
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for id in 0..self.len() {
+            let root = self.find(id);
+            let index = *groups.entry(root).or_insert_with(|| {
+                components.push(Vec::new());
+                components.len() - 1
+            });
+            components[index].push(id);
+        }
+        components
+    }
+}
+
+// Union-find keyed by arbitrary labels, which are assigned dense ids as they are first seen
+#[derive(Debug, Clone)]
+pub struct LabelledDisjointSet<L: Hash + Eq + Clone> {
+    ids: HashMap<L, usize>,
+    labels: Vec<L>,
+    sets: DisjointSet,
+}
+
+impl<L: Hash + Eq + Clone> Default for LabelledDisjointSet<L> {
+    fn default() -> Self {
+        LabelledDisjointSet { ids: HashMap::new(), labels: Vec::new(), sets: DisjointSet::new(0) }
+    }
+}
+
+impl<L: Hash + Eq + Clone> LabelledDisjointSet<L> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, label: L) -> usize {
+        if let Some(id) = self.ids.get(&label) {
+            return *id;
+        }
+        let id = self.sets.add();
+        self.ids.insert(label.clone(), id);
+        self.labels.push(label);
+        id
+    }
+
+    pub fn id(&self, label: &L) -> Option<usize> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: usize) -> &L {
+        &self.labels[id]
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    // Inserts either label if it is new, returns false if they were already joined
+    pub fn union(&mut self, a: L, b: L) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        self.sets.union(a, b)
+    }
+
+    // The label representing the component, or None if the label was never inserted
+    pub fn find(&mut self, label: &L) -> Option<L> {
+        let id = self.id(label)?;
+        let root = self.sets.find(id);
+        Some(self.labels[root].clone())
+    }
+
+    pub fn connected(&mut self, a: &L, b: &L) -> bool {
+        match (self.id(a), self.id(b)) {
+            (Some(a), Some(b)) => self.sets.connected(a, b),
+            _ => false,
+        }
+    }
+
+    pub fn component_count(&self) -> usize {
+        self.sets.component_count()
+    }
+
+    pub fn component_size(&mut self, label: &L) -> Option<usize> {
+        let id = self.id(label)?;
+        Some(self.sets.component_size(id))
+    }
+
+    pub fn component_sizes(&self) -> Vec<usize> {
+        self.sets.component_sizes()
+    }
+
+    pub fn components(&mut self) -> Vec<Vec<L>> {
+        self.sets.components().into_iter()
+            .map(|ids| ids.into_iter().map(|id| self.labels[id].clone()).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod dsu_tests {
+    use super::*;
+
+    #[test]
+    fn union_merges_and_counts_components() {
+        let mut sets = DisjointSet::new(6);
+        assert_eq!(sets.component_count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.component_count(), 3);
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(sets.component_size(2), 4);
+    }
+
+    #[test]
+    fn component_sizes_and_members() {
+        let mut sets = DisjointSet::new(5);
+        sets.union(4, 1);
+        sets.union(3, 0);
+        sets.union(0, 4);
+        assert_eq!(sets.component_sizes(), vec![4, 1]);
+        assert_eq!(sets.components(), vec![vec![0, 1, 3, 4], vec![2]]);
+    }
+
+    #[test]
+    fn add_extends_with_singletons() {
+        let mut sets = DisjointSet::new(0);
+        assert!(sets.is_empty());
+        let a = sets.add();
+        let b = sets.add();
+        assert_eq!(sets.component_count(), 2);
+        sets.union(a, b);
+        assert_eq!(sets.component_count(), 1);
+        assert_eq!(sets.len(), 2);
+    }
+
+    #[test]
+    fn long_chains_stay_shallow() {
+        let mut sets = DisjointSet::new(100_000);
+        for i in 1..100_000 {
+            sets.union(i - 1, i);
+        }
+        assert_eq!(sets.component_count(), 1);
+        assert_eq!(sets.component_size(99_999), 100_000);
+    }
+
+    #[test]
+    fn labelled_membership() {
+        let mut sets = LabelledDisjointSet::new();
+        sets.union("jqt", "rhn");
+        sets.union("rhn", "xhk");
+        sets.union("bvb", "cmg");
+        sets.insert("lone");
+        assert_eq!(sets.len(), 6);
+        assert_eq!(sets.component_count(), 3);
+        assert!(sets.connected(&"jqt", &"xhk"));
+        assert!(!sets.connected(&"jqt", &"cmg"));
+        assert!(!sets.connected(&"jqt", &"missing"));
+        assert_eq!(sets.find(&"xhk"), sets.find(&"jqt"));
+        assert_eq!(sets.find(&"missing"), None);
+        assert_eq!(sets.component_size(&"bvb"), Some(2));
+        assert_eq!(sets.components(), vec![vec!["jqt", "rhn", "xhk"], vec!["bvb", "cmg"], vec!["lone"]]);
+    }
+}
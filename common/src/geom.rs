@@ -0,0 +1,309 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+// Screen orientation: x grows to the east and y grows to the south, matching grid row order
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::North => Direction::East,
+            Direction::East => Direction::South,
+            Direction::South => Direction::West,
+            Direction::West => Direction::North,
+        }
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Direction::North | Direction::South)
+    }
+
+    pub fn to_vector(&self) -> Vector {
+        match self {
+            Direction::North => Vector::new(0, -1),
+            Direction::East => Vector::new(1, 0),
+            Direction::South => Vector::new(0, 1),
+            Direction::West => Vector::new(-1, 0),
+        }
+    }
+}
+
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    // Accepts arrows (^>v<), compass letters (NESW) and screen letters (URDL)
+    fn try_from(c: char) -> Result<Direction, char> {
+        match c {
+            '^' | 'N' | 'U' => Ok(Direction::North),
+            '>' | 'E' | 'R' => Ok(Direction::East),
+            'v' | 'S' | 'D' => Ok(Direction::South),
+            '<' | 'W' | 'L' => Ok(Direction::West),
+            _ => Err(c),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Vector {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Vector {
+    pub const ZERO: Vector = Vector { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Vector {
+        Vector { x, y }
+    }
+
+    pub fn manhattan_length(&self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+
+    pub fn chebyshev_length(&self) -> i64 {
+        self.x.abs().max(self.y.abs())
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<i64> for Vector {
+    type Output = Vector;
+    fn mul(self, other: i64) -> Vector {
+        Vector::new(self.x * other, self.y * other)
+    }
+}
+
+impl AddAssign for Vector {
+    fn add_assign(&mut self, other: Vector) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Vector {
+    fn sub_assign(&mut self, other: Vector) {
+        *self = *self - other;
+    }
+}
+
+impl From<Direction> for Vector {
+    fn from(direction: Direction) -> Vector {
+        direction.to_vector()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    pub fn step(&self, direction: Direction) -> Point {
+        *self + direction.to_vector()
+    }
+
+    pub fn manhattan_distance(&self, other: &Point) -> i64 {
+        (*other - *self).manhattan_length()
+    }
+
+    pub fn chebyshev_distance(&self, other: &Point) -> i64 {
+        (*other - *self).chebyshev_length()
+    }
+
+    pub fn neighbours4(&self) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL.iter().map(move |d| self.step(*d))
+    }
+
+    pub fn neighbours8(&self) -> impl Iterator<Item = Point> + '_ {
+        (-1..=1)
+            .flat_map(|dy| (-1..=1).map(move |dx| Vector::new(dx, dy)))
+            .filter(|v| *v != Vector::ZERO)
+            .map(move |v| *self + v)
+    }
+
+    // Grid indices are (x, y) as usize, so anything left of or above the origin has none
+    pub fn to_index(&self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Point {
+        Point::new(x as i64, y as i64)
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+    fn add(self, other: Vector) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+    fn sub(self, other: Vector) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+    fn sub(self, other: Point) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, other: Vector) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, other: Vector) {
+        *self = *self - other;
+    }
+}
+
+#[cfg(test)]
+mod direction_tests {
+    use super::*;
+
+    #[test]
+    fn turns_cycle_back_to_start() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.turn_left().turn_left().turn_left().turn_left(), direction);
+        }
+        assert_eq!(Direction::North.turn_right(), Direction::East);
+        assert_eq!(Direction::North.turn_left(), Direction::West);
+    }
+
+    #[test]
+    fn opposite_vectors_cancel() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.to_vector() + direction.opposite().to_vector(), Vector::ZERO);
+        }
+    }
+
+    #[test]
+    fn try_from_char_accepts_all_notations() {
+        assert_eq!(Direction::try_from('^'), Ok(Direction::North));
+        assert_eq!(Direction::try_from('R'), Ok(Direction::East));
+        assert_eq!(Direction::try_from('S'), Ok(Direction::South));
+        assert_eq!(Direction::try_from('<'), Ok(Direction::West));
+        assert_eq!(Direction::try_from('x'), Err('x'));
+    }
+}
+
+#[cfg(test)]
+mod point_tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic_between_points_and_vectors() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -2);
+        assert_eq!(b - a, Vector::new(3, -4));
+        assert_eq!(a + (b - a), b);
+        assert_eq!(a + Vector::new(1, 1) * 3, Point::new(4, 5));
+        assert_eq!(-Vector::new(1, -1), Vector::new(-1, 1));
+        let mut c = a;
+        c += Vector::new(2, 2);
+        c -= Vector::new(1, 0);
+        assert_eq!(c, Point::new(2, 4));
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -2);
+        assert_eq!(a.manhattan_distance(&b), 7);
+        assert_eq!(a.chebyshev_distance(&b), 4);
+    }
+
+    #[test]
+    fn step_moves_in_screen_orientation() {
+        let origin = Point::ORIGIN;
+        assert_eq!(origin.step(Direction::North), Point::new(0, -1));
+        assert_eq!(origin.step(Direction::East), Point::new(1, 0));
+    }
+
+    #[test]
+    fn neighbours_exclude_self() {
+        let point = Point::new(5, 5);
+        assert_eq!(point.neighbours4().count(), 4);
+        let neighbours: Vec<Point> = point.neighbours8().collect();
+        assert_eq!(neighbours.len(), 8);
+        assert!(!neighbours.contains(&point));
+        assert!(neighbours.iter().all(|n| n.chebyshev_distance(&point) == 1));
+    }
+
+    #[test]
+    fn to_index_when_negative_returns_none() {
+        assert_eq!(Point::new(3, 4).to_index(), Some((3, 4)));
+        assert_eq!(Point::new(-1, 4).to_index(), None);
+        assert_eq!(Point::new(3, -1).to_index(), None);
+        assert_eq!(Point::from((3, 4)), Point::new(3, 4));
+    }
+}
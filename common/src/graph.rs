@@ -0,0 +1,414 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+pub mod min_cut;
+
+// Adjacency list over labelled nodes. Nodes get dense ids in insertion order, and every
+// structural result is mapped back to the original labels.
+#[derive(Debug, Clone)]
+pub struct Graph<L> {
+    labels: Vec<L>,
+    ids: HashMap<L, usize>,
+    edges: Vec<(usize, usize)>,
+    // Parallel to edges, 1 unless added with add_weighted_edge
+    weights: Vec<u64>,
+    // (neighbour, edge index) pairs, both ends are listed for undirected graphs
+    adjacency: Vec<Vec<(usize, usize)>>,
+    directed: bool,
+}
+
+impl<L: Hash + Eq + Clone> Graph<L> {
+    pub fn new(directed: bool) -> Graph<L> {
+        Graph {
+            labels: Vec::new(),
+            ids: HashMap::new(),
+            edges: Vec::new(),
+            weights: Vec::new(),
+            adjacency: Vec::new(),
+            directed,
+        }
+    }
+
+    pub fn directed_from_edges<I: IntoIterator<Item = (L, L)>>(edges: I) -> Graph<L> {
+        let mut graph = Graph::new(true);
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    pub fn undirected_from_edges<I: IntoIterator<Item = (L, L)>>(edges: I) -> Graph<L> {
+        let mut graph = Graph::new(false);
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    pub fn add_node(&mut self, label: L) -> usize {
+        if let Some(id) = self.ids.get(&label) {
+            return *id;
+        }
+        let id = self.labels.len();
+        self.ids.insert(label.clone(), id);
+        self.labels.push(label);
+        self.adjacency.push(Vec::new());
+        id
+    }
+
+    pub fn add_edge(&mut self, from: L, to: L) {
+        self.add_weighted_edge(from, to, 1);
+    }
+
+    pub fn add_weighted_edge(&mut self, from: L, to: L, weight: u64) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        let edge = self.edges.len();
+        self.edges.push((from, to));
+        self.weights.push(weight);
+        self.adjacency[from].push((to, edge));
+        if !self.directed {
+            self.adjacency[to].push((from, edge));
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn id(&self, label: &L) -> Option<usize> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn label(&self, id: usize) -> &L {
+        &self.labels[id]
+    }
+
+    pub fn labels(&self) -> &[L] {
+        &self.labels
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (&L, &L)> {
+        self.edges.iter().map(|(a, b)| (&self.labels[*a], &self.labels[*b]))
+    }
+
+    pub fn edge_weights(&self) -> impl Iterator<Item = (&L, &L, u64)> {
+        self.edges.iter().zip(self.weights.iter())
+            .map(|((a, b), weight)| (&self.labels[*a], &self.labels[*b], *weight))
+    }
+
+    pub fn neighbours(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.adjacency[id].iter().map(|(neighbour, _)| *neighbour)
+    }
+
+    fn to_labels(&self, ids: &[usize]) -> Vec<L> {
+        ids.iter().map(|id| self.labels[*id].clone()).collect()
+    }
+
+    // Tarjan's algorithm, components come out in reverse topological order
+    pub fn strongly_connected_components(&self) -> Vec<Vec<L>> {
+        let n = self.node_count();
+        let mut index = vec![usize::MAX; n];
+        let mut low_link = vec![0; n];
+        let mut on_stack = vec![false; n];
+        let mut stack: Vec<usize> = Vec::new();
+        let mut components: Vec<Vec<L>> = Vec::new();
+        let mut next_index = 0;
+
+        for root in 0..n {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            // Explicit call stack of (node, next adjacency position) to avoid deep recursion
+            let mut call_stack: Vec<(usize, usize)> = vec![(root, 0)];
+            index[root] = next_index;
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((node, position)) = call_stack.pop() {
+                if let Some((next, _)) = self.adjacency[node].get(position) {
+                    call_stack.push((node, position + 1));
+                    if index[*next] == usize::MAX {
+                        index[*next] = next_index;
+                        low_link[*next] = next_index;
+                        next_index += 1;
+                        stack.push(*next);
+                        on_stack[*next] = true;
+                        call_stack.push((*next, 0));
+                    } else if on_stack[*next] {
+                        low_link[node] = low_link[node].min(index[*next]);
+                    }
+                    continue;
+                }
+                if low_link[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(self.to_labels(&component));
+                }
+                if let Some((parent, _)) = call_stack.last() {
+                    low_link[*parent] = low_link[*parent].min(low_link[node]);
+                }
+            }
+        }
+        components
+    }
+
+    // Kahn's algorithm. On failure the error holds one cycle, listed in edge order.
+    pub fn topological_sort(&self) -> Result<Vec<L>, Vec<L>> {
+        let n = self.node_count();
+        let mut in_degree = vec![0; n];
+        for (_, to) in &self.edges {
+            in_degree[*to] += 1;
+        }
+        let mut queue: VecDeque<usize> = (0..n).filter(|id| in_degree[*id] == 0).collect();
+        let mut order = Vec::with_capacity(n);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for (next, _) in &self.adjacency[node] {
+                in_degree[*next] -= 1;
+                if in_degree[*next] == 0 {
+                    queue.push_back(*next);
+                }
+            }
+        }
+        if order.len() == n {
+            return Ok(self.to_labels(&order));
+        }
+
+        // Every node left over has an incoming edge from another left over node, so walking
+        // backwards along those edges must eventually repeat a node
+        let mut predecessor = vec![usize::MAX; n];
+        for (from, to) in &self.edges {
+            if in_degree[*from] > 0 && in_degree[*to] > 0 {
+                predecessor[*to] = *from;
+            }
+        }
+        let mut seen = vec![false; n];
+        let mut node = (0..n).find(|id| in_degree[*id] > 0).unwrap();
+        while !seen[node] {
+            seen[node] = true;
+            node = predecessor[node];
+        }
+        let mut cycle = vec![node];
+        let mut current = predecessor[node];
+        while current != node {
+            cycle.push(current);
+            current = predecessor[current];
+        }
+        cycle.reverse();
+        Err(self.to_labels(&cycle))
+    }
+
+    fn undirected_adjacency(&self) -> Vec<Vec<(usize, usize)>> {
+        if !self.directed {
+            return self.adjacency.clone();
+        }
+        let mut adjacency = vec![Vec::new(); self.node_count()];
+        for (edge, (from, to)) in self.edges.iter().enumerate() {
+            adjacency[*from].push((*to, edge));
+            adjacency[*to].push((*from, edge));
+        }
+        adjacency
+    }
+
+    // Components of the underlying undirected graph
+    pub fn connected_components(&self) -> Vec<Vec<L>> {
+        let adjacency = self.undirected_adjacency();
+        let mut visited = vec![false; self.node_count()];
+        let mut components = Vec::new();
+        for root in 0..self.node_count() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut component = vec![root];
+            let mut stack = vec![root];
+            while let Some(node) = stack.pop() {
+                for (next, _) in &adjacency[node] {
+                    if !visited[*next] {
+                        visited[*next] = true;
+                        component.push(*next);
+                        stack.push(*next);
+                    }
+                }
+            }
+            components.push(self.to_labels(&component));
+        }
+        components
+    }
+
+    // Lowlink DFS over the underlying undirected graph, returning (bridge edges, cut vertices)
+    fn low_link_analysis(&self) -> (Vec<usize>, Vec<usize>) {
+        let n = self.node_count();
+        let adjacency = self.undirected_adjacency();
+        let mut discovery = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut bridges = Vec::new();
+        let mut is_cut = vec![false; n];
+        let mut time = 0;
+
+        for root in 0..n {
+            if discovery[root] != usize::MAX {
+                continue;
+            }
+            discovery[root] = time;
+            low[root] = time;
+            time += 1;
+            let mut root_children = 0;
+            // (node, edge used to reach it, next adjacency position)
+            let mut call_stack: Vec<(usize, usize, usize)> = vec![(root, usize::MAX, 0)];
+            while let Some((node, parent_edge, position)) = call_stack.pop() {
+                if let Some((next, edge)) = adjacency[node].get(position) {
+                    call_stack.push((node, parent_edge, position + 1));
+                    if *edge == parent_edge {
+                        continue;
+                    }
+                    if discovery[*next] == usize::MAX {
+                        discovery[*next] = time;
+                        low[*next] = time;
+                        time += 1;
+                        if node == root {
+                            root_children += 1;
+                        }
+                        call_stack.push((*next, *edge, 0));
+                    } else {
+                        low[node] = low[node].min(discovery[*next]);
+                    }
+                    continue;
+                }
+                if let Some((parent, _, _)) = call_stack.last() {
+                    let parent = *parent;
+                    low[parent] = low[parent].min(low[node]);
+                    if low[node] > discovery[parent] {
+                        bridges.push(parent_edge);
+                    }
+                    if parent != root && low[node] >= discovery[parent] {
+                        is_cut[parent] = true;
+                    }
+                }
+            }
+            if root_children > 1 {
+                is_cut[root] = true;
+            }
+        }
+        (bridges, (0..n).filter(|id| is_cut[*id]).collect())
+    }
+
+    pub fn bridges(&self) -> Vec<(L, L)> {
+        let (bridges, _) = self.low_link_analysis();
+        bridges.iter()
+            .map(|edge| {
+                let (from, to) = self.edges[*edge];
+                (self.labels[from].clone(), self.labels[to].clone())
+            })
+            .collect()
+    }
+
+    pub fn articulation_points(&self) -> Vec<L> {
+        let (_, cut_vertices) = self.low_link_analysis();
+        self.to_labels(&cut_vertices)
+    }
+}
+
+#[cfg(test)]
+mod graph_tests {
+    use super::*;
+
+    fn sorted<T: Ord>(mut values: Vec<T>) -> Vec<T> {
+        values.sort();
+        values
+    }
+
+    fn sorted_groups(groups: Vec<Vec<&str>>) -> Vec<Vec<&str>> {
+        sorted(groups.into_iter().map(sorted).collect())
+    }
+
+    #[test]
+    fn add_edge_reuses_existing_labels() {
+        let graph = Graph::directed_from_edges(vec![("a", "b"), ("b", "c"), ("a", "c")]);
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.id(&"b"), Some(1));
+        assert_eq!(*graph.label(2), "c");
+        assert_eq!(graph.neighbours(0).collect::<Vec<usize>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn strongly_connected_components_groups_cycles() {
+        let graph = Graph::directed_from_edges(vec![
+            ("a", "b"), ("b", "c"), ("c", "a"),
+            ("c", "d"), ("d", "e"), ("e", "d"),
+            ("e", "f"),
+        ]);
+        let components = graph.strongly_connected_components();
+        assert_eq!(sorted_groups(components.clone()), vec![vec!["a", "b", "c"], vec!["d", "e"], vec!["f"]]);
+        // Reverse topological order: sinks first
+        assert_eq!(components[0], vec!["f"]);
+    }
+
+    #[test]
+    fn topological_sort_when_acyclic_orders_edges() {
+        let graph = Graph::directed_from_edges(vec![("shirt", "tie"), ("tie", "jacket"), ("trousers", "shoes"), ("trousers", "belt"), ("belt", "jacket")]);
+        let order = graph.topological_sort().unwrap();
+        let position = |label: &str| order.iter().position(|l| *l == label).unwrap();
+        for (from, to) in graph.edges() {
+            assert!(position(from) < position(to));
+        }
+    }
+
+    #[test]
+    fn topological_sort_when_cyclic_reports_cycle() {
+        let graph = Graph::directed_from_edges(vec![("a", "b"), ("b", "c"), ("c", "d"), ("d", "b"), ("d", "e")]);
+        let cycle = graph.topological_sort().unwrap_err();
+        assert_eq!(sorted(cycle.clone()), vec!["b", "c", "d"]);
+        for i in 0..cycle.len() {
+            let from = cycle[i];
+            let to = cycle[(i + 1) % cycle.len()];
+            assert!(graph.edges().any(|(a, b)| *a == from && *b == to));
+        }
+    }
+
+    #[test]
+    fn connected_components_ignore_direction() {
+        let graph = Graph::directed_from_edges(vec![("a", "b"), ("c", "b"), ("d", "e")]);
+        assert_eq!(sorted_groups(graph.connected_components()), vec![vec!["a", "b", "c"], vec!["d", "e"]]);
+    }
+
+    #[test]
+    fn bridges_and_articulation_points() {
+        // Two triangles joined by the bridge c-d, with a tail e-f hanging off
+        let graph = Graph::undirected_from_edges(vec![
+            ("a", "b"), ("b", "c"), ("c", "a"),
+            ("c", "d"),
+            ("d", "e"), ("e", "g"), ("g", "d"),
+            ("e", "f"),
+        ]);
+        let bridges = sorted(graph.bridges().into_iter().map(|(a, b)| sorted(vec![a, b])).collect());
+        assert_eq!(bridges, vec![vec!["c", "d"], vec!["e", "f"]]);
+        assert_eq!(sorted(graph.articulation_points()), vec!["c", "d", "e"]);
+    }
+
+    #[test]
+    fn bridges_when_parallel_edges_are_not_bridges() {
+        let graph = Graph::undirected_from_edges(vec![("a", "b"), ("a", "b"), ("b", "c")]);
+        assert_eq!(graph.bridges(), vec![("b", "c")]);
+        assert_eq!(graph.articulation_points(), vec!["b"]);
+    }
+}
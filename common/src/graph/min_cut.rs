@@ -0,0 +1,313 @@
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+
+use super::Graph;
+use crate::dsu::DisjointSet;
+
+// A global minimum cut of the underlying undirected graph, edge directions are ignored
+#[derive(Debug, Clone, PartialEq)]
+pub struct MinCut<L> {
+    pub weight: u64,
+    pub cut_edges: Vec<(L, L)>,
+    pub partitions: (Vec<L>, Vec<L>),
+}
+
+// Undirected weighted multigraph over dense ids, the working form for both algorithms
+struct WeightedEdges {
+    node_count: usize,
+    edges: Vec<(usize, usize, u64)>,
+}
+
+impl WeightedEdges {
+    fn from_graph<L: Hash + Eq + Clone>(graph: &Graph<L>) -> WeightedEdges {
+        let edges = graph.edges.iter().zip(graph.weights.iter())
+            .filter(|((a, b), _)| a != b)
+            .map(|((a, b), weight)| (*a, *b, *weight))
+            .collect();
+        WeightedEdges { node_count: graph.node_count(), edges }
+    }
+}
+
+// Splitmix64, enough randomness for contraction order without pulling in a dependency
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn new(seed: u64) -> SplitMix64 {
+        SplitMix64 { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in (0, 1]
+    fn next_unit(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn build_result<L: Hash + Eq + Clone>(graph: &Graph<L>, side: &[bool]) -> MinCut<L> {
+    let mut weight = 0;
+    let mut cut_edges = Vec::new();
+    for ((a, b), edge_weight) in graph.edges.iter().zip(graph.weights.iter()) {
+        if side[*a] != side[*b] {
+            weight += edge_weight;
+            cut_edges.push((graph.labels[*a].clone(), graph.labels[*b].clone()));
+        }
+    }
+    let (inside, outside): (Vec<usize>, Vec<usize>) = (0..graph.node_count()).partition(|id| side[*id]);
+    MinCut { weight, cut_edges, partitions: (graph.to_labels(&inside), graph.to_labels(&outside)) }
+}
+
+// Returns the cut weight and which nodes sit on one side of it
+fn stoer_wagner_edges(graph: &WeightedEdges) -> (u64, Vec<bool>) {
+    let n = graph.node_count;
+    let mut adjacency: Vec<HashMap<usize, u64>> = vec![HashMap::new(); n];
+    for (a, b, weight) in &graph.edges {
+        *adjacency[*a].entry(*b).or_insert(0) += weight;
+        *adjacency[*b].entry(*a).or_insert(0) += weight;
+    }
+    let mut members: Vec<Vec<usize>> = (0..n).map(|id| vec![id]).collect();
+    let mut active: Vec<usize> = (0..n).collect();
+    let mut best: (u64, Vec<usize>) = (u64::MAX, Vec::new());
+
+    while active.len() > 1 {
+        // Maximum adjacency ordering, the heap holds stale entries that are skipped on pop
+        let mut connection = vec![0u64; n];
+        let mut added = vec![false; n];
+        let mut heap = BinaryHeap::new();
+        let mut order: Vec<usize> = Vec::with_capacity(active.len());
+        let mut unvisited = active.iter();
+        while order.len() < active.len() {
+            let node = match heap.pop() {
+                Some((key, node)) if added[node] || key != connection[node] => continue,
+                Some((_, node)) => node,
+                // The rest of the graph is disconnected from everything added so far
+                None => *unvisited.find(|id| !added[**id]).unwrap(),
+            };
+            added[node] = true;
+            order.push(node);
+            for (next, weight) in &adjacency[node] {
+                if !added[*next] {
+                    connection[*next] += weight;
+                    heap.push((connection[*next], *next));
+                }
+            }
+        }
+
+        let last = order[order.len() - 1];
+        let previous = order[order.len() - 2];
+        if connection[last] < best.0 {
+            best = (connection[last], members[last].clone());
+        }
+
+        // Merge last into previous
+        let last_edges = std::mem::take(&mut adjacency[last]);
+        for (next, weight) in last_edges {
+            adjacency[next].remove(&last);
+            if next != previous {
+                *adjacency[previous].entry(next).or_insert(0) += weight;
+                *adjacency[next].entry(previous).or_insert(0) += weight;
+            }
+        }
+        let moved = std::mem::take(&mut members[last]);
+        members[previous].extend(moved);
+        active.retain(|id| *id != last);
+    }
+
+    let mut side = vec![false; n];
+    for id in best.1 {
+        side[id] = true;
+    }
+    (best.0, side)
+}
+
+// Randomly contracts edges until target super nodes remain. Sorting by exponential keys scaled
+// by weight gives the same order as repeatedly picking an edge with probability by weight.
+fn contract(graph: &WeightedEdges, target: usize, rng: &mut SplitMix64) -> (WeightedEdges, Vec<usize>) {
+    let mut keyed: Vec<(f64, usize)> = graph.edges.iter().enumerate()
+        .map(|(i, (_, _, weight))| (-rng.next_unit().ln() / *weight as f64, i))
+        .collect();
+    keyed.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut sets = DisjointSet::new(graph.node_count);
+    for (_, i) in keyed {
+        if sets.component_count() <= target {
+            break;
+        }
+        let (a, b, _) = graph.edges[i];
+        sets.union(a, b);
+    }
+
+    let mut compact: HashMap<usize, usize> = HashMap::new();
+    let mapping: Vec<usize> = (0..graph.node_count)
+        .map(|id| {
+            let root = sets.find(id);
+            let next = compact.len();
+            *compact.entry(root).or_insert(next)
+        })
+        .collect();
+    let mut merged: HashMap<(usize, usize), u64> = HashMap::new();
+    for (a, b, weight) in &graph.edges {
+        let (a, b) = (mapping[*a], mapping[*b]);
+        if a != b {
+            *merged.entry((a.min(b), a.max(b))).or_insert(0) += weight;
+        }
+    }
+    // Sorted so that the seed alone decides the contraction order
+    let mut edges: Vec<(usize, usize, u64)> = merged.into_iter().map(|((a, b), weight)| (a, b, weight)).collect();
+    edges.sort_unstable();
+    (WeightedEdges { node_count: compact.len(), edges }, mapping)
+}
+
+fn karger_stein_edges(graph: &WeightedEdges, rng: &mut SplitMix64) -> (u64, Vec<bool>) {
+    let n = graph.node_count;
+    if n <= 6 {
+        return stoer_wagner_edges(graph);
+    }
+    let target = (1.0 + n as f64 / std::f64::consts::SQRT_2).ceil() as usize;
+    let mut best: Option<(u64, Vec<bool>)> = None;
+    for _ in 0..2 {
+        let (contracted, mapping) = contract(graph, target, rng);
+        if contracted.node_count == n {
+            // Too many disconnected pieces to contract any further, so a zero cut exists
+            return stoer_wagner_edges(graph);
+        }
+        let (weight, side) = karger_stein_edges(&contracted, rng);
+        if best.as_ref().is_none_or(|(b, _)| weight < *b) {
+            best = Some((weight, mapping.iter().map(|id| side[*id]).collect()));
+        }
+    }
+    best.unwrap()
+}
+
+// Deterministic and exact. Returns None when there are fewer than two nodes to separate.
+pub fn stoer_wagner<L: Hash + Eq + Clone>(graph: &Graph<L>) -> Option<MinCut<L>> {
+    if graph.node_count() < 2 {
+        return None;
+    }
+    let (_, side) = stoer_wagner_edges(&WeightedEdges::from_graph(graph));
+    Some(build_result(graph, &side))
+}
+
+// Monte Carlo, each trial finds a minimum cut with probability around 1 / log(n), so the best
+// of O(log^2 n) trials is minimum with high probability. The same seed gives the same result.
+pub fn karger_stein<L: Hash + Eq + Clone>(graph: &Graph<L>, seed: u64, trials: usize) -> Option<MinCut<L>> {
+    if graph.node_count() < 2 || trials == 0 {
+        return None;
+    }
+    let edges = WeightedEdges::from_graph(graph);
+    let mut rng = SplitMix64::new(seed);
+    let (_, side) = (0..trials)
+        .map(|_| karger_stein_edges(&edges, &mut rng))
+        .min_by_key(|(weight, _)| *weight)
+        .unwrap();
+    Some(build_result(graph, &side))
+}
+
+#[cfg(test)]
+mod min_cut_tests {
+    use super::*;
+
+    fn sorted<T: Ord>(mut values: Vec<T>) -> Vec<T> {
+        values.sort();
+        values
+    }
+
+    // Two dense random clusters of the given size joined by the planted edges
+    fn planted_cut(cluster_size: u32, planted: &[(u32, u32)], seed: u64) -> Graph<u32> {
+        let mut rng = SplitMix64::new(seed);
+        let mut graph = Graph::new(false);
+        for offset in [0, cluster_size] {
+            for a in 0..cluster_size {
+                for b in a + 1..cluster_size {
+                    if rng.next_u64() & 1 == 0 || b == a + 1 {
+                        graph.add_edge(offset + a, offset + b);
+                    }
+                }
+            }
+        }
+        for (a, b) in planted {
+            graph.add_edge(*a, cluster_size + *b);
+        }
+        graph
+    }
+
+    fn paper_example() -> Graph<u32> {
+        // The example graph from the Stoer-Wagner paper, minimum cut {3, 4, 7, 8} of weight 4
+        let mut graph = Graph::new(false);
+        for (a, b, weight) in [
+            (1, 2, 2), (1, 5, 3), (2, 3, 3), (2, 5, 2), (2, 6, 2), (3, 4, 4),
+            (3, 7, 2), (4, 7, 2), (4, 8, 2), (5, 6, 3), (6, 7, 1), (7, 8, 3),
+        ] {
+            graph.add_weighted_edge(a, b, weight);
+        }
+        graph
+    }
+
+    fn sorted_partitions(cut: &MinCut<u32>) -> Vec<Vec<u32>> {
+        sorted(vec![sorted(cut.partitions.0.clone()), sorted(cut.partitions.1.clone())])
+    }
+
+    #[test]
+    fn stoer_wagner_on_weighted_example() {
+        let cut = stoer_wagner(&paper_example()).unwrap();
+        assert_eq!(cut.weight, 4);
+        assert_eq!(sorted_partitions(&cut), vec![vec![1, 2, 5, 6], vec![3, 4, 7, 8]]);
+        assert_eq!(sorted(cut.cut_edges), vec![(2, 3), (6, 7)]);
+    }
+
+    #[test]
+    fn stoer_wagner_finds_planted_cut() {
+        let planted = [(0, 3), (7, 11), (19, 2)];
+        let graph = planted_cut(20, &planted, 7);
+        let cut = stoer_wagner(&graph).unwrap();
+        assert_eq!(cut.weight, 3);
+        assert_eq!(sorted_partitions(&cut), vec![(0..20).collect::<Vec<u32>>(), (20..40).collect()]);
+        assert_eq!(sorted(cut.cut_edges), vec![(0, 23), (7, 31), (19, 22)]);
+    }
+
+    #[test]
+    fn karger_stein_finds_planted_cut() {
+        let graph = planted_cut(20, &[(4, 4), (5, 9), (12, 0)], 11);
+        let cut = karger_stein(&graph, 42, 10).unwrap();
+        assert_eq!(cut.weight, 3);
+        assert_eq!(cut.partitions.0.len() * cut.partitions.1.len(), 400);
+        assert_eq!(karger_stein(&graph, 42, 10), Some(cut));
+    }
+
+    #[test]
+    fn karger_stein_on_weighted_example() {
+        let cut = karger_stein(&paper_example(), 1, 5).unwrap();
+        assert_eq!(cut.weight, 4);
+    }
+
+    #[test]
+    fn disconnected_graph_has_zero_cut() {
+        let graph = Graph::undirected_from_edges(vec![(1, 2), (2, 3), (4, 5)]);
+        let cut = stoer_wagner(&graph).unwrap();
+        assert_eq!(cut.weight, 0);
+        assert!(cut.cut_edges.is_empty());
+        assert_eq!(karger_stein(&graph, 3, 3).unwrap().weight, 0);
+    }
+
+    #[test]
+    fn karger_stein_with_many_components() {
+        let graph = Graph::undirected_from_edges((0..10).map(|i| (2 * i, 2 * i + 1)));
+        assert_eq!(karger_stein(&graph, 5, 2).unwrap().weight, 0);
+    }
+
+    #[test]
+    fn too_small_graph_has_no_cut() {
+        let mut graph: Graph<u32> = Graph::new(false);
+        assert_eq!(stoer_wagner(&graph), None);
+        graph.add_node(1);
+        assert_eq!(karger_stein(&graph, 0, 1), None);
+    }
+}
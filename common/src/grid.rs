@@ -0,0 +1,558 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::geom::Point;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GridError {
+    Empty,
+    NotRectangular { line: usize, expected: usize, found: usize },
+    WrongCellCount { expected: usize, found: usize },
+    InvalidChar { x: usize, y: usize, c: char },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "Grid has no cells"),
+            GridError::NotRectangular { line, expected, found } =>
+                write!(f, "Line {} has width {}, expected {}", line, found, expected),
+            GridError::WrongCellCount { expected, found } =>
+                write!(f, "Grid needs {} cells, found {}", expected, found),
+            GridError::InvalidChar { x, y, c } =>
+                write!(f, "Invalid character {:?} at {}, {}", c, x, y),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+const NEIGHBOUR_OFFSETS_4: [(i64, i64); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const NEIGHBOUR_OFFSETS_8: [(i64, i64); 8] = [
+    (-1, -1), (0, -1), (1, -1),
+    (-1, 0), (1, 0),
+    (-1, 1), (0, 1), (1, 1),
+];
+
+// Rectangular grid stored row-major, addressed by (x, y) with y = 0 as the first line
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> where T: Clone {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Result<Grid<T>, GridError> {
+        if width * height != cells.len() {
+            return Err(GridError::WrongCellCount { expected: width * height, found: cells.len() });
+        }
+        Ok(Grid { width, height, cells })
+    }
+
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>, GridError> {
+        let height = rows.len();
+        let width = rows.first().map(|r| r.len()).ok_or(GridError::Empty)?;
+        let mut cells = Vec::with_capacity(width * height);
+        for (y, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(GridError::NotRectangular { line: y, expected: width, found: row.len() });
+            }
+            cells.extend(row);
+        }
+        Ok(Grid { width, height, cells })
+    }
+
+    pub fn parse<S, F>(lines: &[S], parse_char: F) -> Result<Grid<T>, GridError>
+        where S: AsRef<str>, F: Fn(char) -> Option<T> {
+        let mut rows = Vec::with_capacity(lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            let mut row = Vec::new();
+            for (x, c) in line.as_ref().chars().enumerate() {
+                row.push(parse_char(c).ok_or(GridError::InvalidChar { x, y, c })?);
+            }
+            rows.push(row);
+        }
+        Grid::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if self.in_bounds(x, y) { Some(&self.cells[y * self.width + x]) } else { None }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        if self.in_bounds(x, y) { Some(&mut self.cells[y * self.width + x]) } else { None }
+    }
+
+    pub fn get_at(&self, point: Point) -> Option<&T> {
+        let (x, y) = point.to_index()?;
+        self.get(x, y)
+    }
+
+    pub fn get_at_mut(&mut self, point: Point) -> Option<&mut T> {
+        let (x, y) = point.to_index()?;
+        self.get_mut(x, y)
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: T) {
+        self[(x, y)] = value;
+    }
+
+    pub fn neighbours4(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_positions(x, y, &NEIGHBOUR_OFFSETS_4)
+    }
+
+    pub fn neighbours8(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.offset_positions(x, y, &NEIGHBOUR_OFFSETS_8)
+    }
+
+    fn offset_positions<'a>(&'a self, x: usize, y: usize, offsets: &'static [(i64, i64)])
+        -> impl Iterator<Item = (usize, usize)> + 'a {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let nx = usize::try_from(x as i64 + dx).ok()?;
+            let ny = usize::try_from(y as i64 + dy).ok()?;
+            if self.in_bounds(nx, ny) { Some((nx, ny)) } else { None }
+        })
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "Column {} out of range for width {}", x, self.width);
+        self.cells.iter().skip(x).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn find(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter().find(|(_, cell)| predicate(cell)).map(|(position, _)| position)
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+
+    pub fn render(&self, to_char: impl Fn(&T) -> char) -> String {
+        let mut s = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            s.extend(row.iter().map(&to_char));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(self.in_bounds(x, y), "Position {}, {} is outside {}x{} grid", x, y, self.width, self.height);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(self.in_bounds(x, y), "Position {}, {} is outside {}x{} grid", x, y, self.width, self.height);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl<T: Copy + Into<char>> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render(|cell| (*cell).into()))
+    }
+}
+
+// The eight symmetries of a rectangle. Each maps a position in the transformed view back to
+// a position in the original grid: optionally swap the axes, then optionally mirror either
+// axis of the original.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Transform {
+    Identity,
+    RotateClockwise,
+    Rotate180,
+    RotateCounterClockwise,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+    AntiTranspose,
+}
+
+impl Transform {
+    pub const ALL: [Transform; 8] = [
+        Transform::Identity,
+        Transform::RotateClockwise,
+        Transform::Rotate180,
+        Transform::RotateCounterClockwise,
+        Transform::FlipHorizontal,
+        Transform::FlipVertical,
+        Transform::Transpose,
+        Transform::AntiTranspose,
+    ];
+
+    // (swap axes, flip x, flip y)
+    fn parts(&self) -> (bool, bool, bool) {
+        match self {
+            Transform::Identity => (false, false, false),
+            Transform::FlipHorizontal => (false, true, false),
+            Transform::FlipVertical => (false, false, true),
+            Transform::Rotate180 => (false, true, true),
+            Transform::Transpose => (true, false, false),
+            Transform::RotateClockwise => (true, false, true),
+            Transform::RotateCounterClockwise => (true, true, false),
+            Transform::AntiTranspose => (true, true, true),
+        }
+    }
+
+    fn from_parts(parts: (bool, bool, bool)) -> Transform {
+        *Transform::ALL.iter().find(|t| t.parts() == parts).unwrap()
+    }
+
+    pub fn swaps_axes(&self) -> bool {
+        self.parts().0
+    }
+
+    pub fn inverse(&self) -> Transform {
+        let (swap, flip_x, flip_y) = self.parts();
+        if swap {
+            Transform::from_parts((swap, flip_y, flip_x))
+        } else {
+            *self
+        }
+    }
+
+    // Applying the result is the same as applying self to the grid, then other to that view
+    pub fn then(&self, other: Transform) -> Transform {
+        let (swap_1, flip_x_1, flip_y_1) = self.parts();
+        let (swap_2, flip_x_2, flip_y_2) = other.parts();
+        let (inner_x, inner_y) = if swap_1 { (flip_y_2, flip_x_2) } else { (flip_x_2, flip_y_2) };
+        Transform::from_parts((swap_1 ^ swap_2, flip_x_1 ^ inner_x, flip_y_1 ^ inner_y))
+    }
+
+    fn dimensions(&self, width: usize, height: usize) -> (usize, usize) {
+        if self.swaps_axes() { (height, width) } else { (width, height) }
+    }
+
+    fn source_position(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        let (swap, flip_x, flip_y) = self.parts();
+        let (ax, ay) = if swap { (y, x) } else { (x, y) };
+        (
+            if flip_x { width - 1 - ax } else { ax },
+            if flip_y { height - 1 - ay } else { ay },
+        )
+    }
+}
+
+// A transformed, read-only window onto a grid that never copies cells
+#[derive(Debug)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    transform: Transform,
+}
+
+impl<T> Clone for GridView<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for GridView<'_, T> {}
+
+impl<'a, T> GridView<'a, T> {
+    pub fn width(&self) -> usize {
+        self.transform.dimensions(self.grid.width, self.grid.height).0
+    }
+
+    pub fn height(&self) -> usize {
+        self.transform.dimensions(self.grid.width, self.grid.height).1
+    }
+
+    pub fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let (sx, sy) = self.transform.source_position(x, y, self.grid.width, self.grid.height);
+        self.grid.get(sx, sy)
+    }
+
+    pub fn view(&self, transform: Transform) -> GridView<'a, T> {
+        GridView { grid: self.grid, transform: self.transform.then(transform) }
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = &'a T> {
+        let view = *self;
+        (0..self.width()).map(move |x| view.get(x, y).unwrap())
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T>> {
+        let view = *self;
+        (0..self.height()).map(move |y| view.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &'a T> {
+        let view = *self;
+        (0..self.height()).map(move |y| view.get(x, y).unwrap())
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T>> {
+        let view = *self;
+        (0..self.width()).map(move |x| view.column(x))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &'a T)> {
+        let view = *self;
+        let width = self.width();
+        (0..self.height())
+            .flat_map(move |y| (0..width).map(move |x| (x, y)))
+            .map(move |(x, y)| ((x, y), view.get(x, y).unwrap()))
+    }
+
+    pub fn to_grid(&self) -> Grid<T> where T: Clone {
+        Grid {
+            width: self.width(),
+            height: self.height(),
+            cells: self.iter().map(|(_, cell)| cell.clone()).collect(),
+        }
+    }
+
+    pub fn render(&self, to_char: impl Fn(&T) -> char) -> String {
+        let mut s = String::with_capacity((self.width() + 1) * self.height());
+        for row in self.rows() {
+            s.extend(row.map(&to_char));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<T> Index<(usize, usize)> for GridView<'_, T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!("Position {}, {} is outside {}x{} view", x, y, self.width(), self.height())
+        })
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn view(&self, transform: Transform) -> GridView<'_, T> {
+        GridView { grid: self, transform }
+    }
+
+    pub fn transformed(&self, transform: Transform) -> Grid<T> where T: Clone {
+        self.view(transform).to_grid()
+    }
+}
+
+#[cfg(test)]
+mod grid_tests {
+    use super::*;
+
+    fn build_grid() -> Grid<char> {
+        Grid::parse(&["abc", "def"], Some).unwrap()
+    }
+
+    #[test]
+    fn parse_when_rectangular_returns_grid() {
+        let grid = build_grid();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.height(), 2);
+        assert_eq!(grid[(2, 1)], 'f');
+        assert_eq!(grid.get(1, 0), Some(&'b'));
+    }
+
+    #[test]
+    fn parse_when_ragged_returns_error() {
+        let result = Grid::parse(&["abc", "de"], Some);
+        assert_eq!(result, Err(GridError::NotRectangular { line: 1, expected: 3, found: 2 }));
+    }
+
+    #[test]
+    fn from_cells_when_count_is_wrong_returns_error() {
+        let result = Grid::from_cells(3, 2, vec![0; 7]);
+        assert_eq!(result, Err(GridError::WrongCellCount { expected: 6, found: 7 }));
+        assert!(Grid::from_cells(3, 2, vec![0; 6]).is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn column_when_out_of_range_panics() {
+        build_grid().column(3).count();
+    }
+
+    #[test]
+    fn parse_when_mapping_fails_returns_error() {
+        let result = Grid::parse(&["..", ".x"], |c| if c == '.' { Some(0) } else { None });
+        assert_eq!(result, Err(GridError::InvalidChar { x: 1, y: 1, c: 'x' }));
+    }
+
+    #[test]
+    fn parse_when_no_lines_returns_error() {
+        let lines: Vec<String> = Vec::new();
+        assert_eq!(Grid::parse(&lines, Some), Err(GridError::Empty));
+    }
+
+    #[test]
+    fn get_when_out_of_bounds_returns_none() {
+        let grid = build_grid();
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+    }
+
+    #[test]
+    fn get_at_when_negative_returns_none() {
+        let grid = build_grid();
+        assert_eq!(grid.get_at(Point::new(2, 1)), Some(&'f'));
+        assert_eq!(grid.get_at(Point::new(-1, 0)), None);
+        assert_eq!(grid.get_at(Point::new(0, 2)), None);
+    }
+
+    #[test]
+    fn neighbours4_at_corner_stays_in_bounds() {
+        let grid = build_grid();
+        let neighbours: Vec<(usize, usize)> = grid.neighbours4(0, 0).collect();
+        assert_eq!(neighbours, vec![(1, 0), (0, 1)]);
+    }
+
+    #[test]
+    fn neighbours8_in_middle_returns_all_in_bounds() {
+        let grid = build_grid();
+        let neighbours: Vec<(usize, usize)> = grid.neighbours8(1, 1).collect();
+        assert_eq!(neighbours, vec![(0, 0), (1, 0), (2, 0), (0, 1), (2, 1)]);
+    }
+
+    #[test]
+    fn rows_and_columns_iterate_in_order() {
+        let grid = build_grid();
+        assert_eq!(grid.row(1), &['d', 'e', 'f']);
+        let columns: Vec<String> = grid.columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, vec!["ad", "be", "cf"]);
+        assert_eq!(grid.rows().count(), 2);
+    }
+
+    #[test]
+    fn find_returns_first_position() {
+        let grid = build_grid();
+        assert_eq!(grid.find(|c| *c == 'e'), Some((1, 1)));
+        assert_eq!(grid.find(|c| *c == 'z'), None);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let grid = build_grid();
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+        let upper = grid.map(|c| c.to_ascii_uppercase());
+        assert_eq!(upper.render(|c| *c), "ABC\nDEF\n");
+    }
+}
+
+#[cfg(test)]
+mod grid_view_tests {
+    use super::*;
+
+    fn build_grid() -> Grid<char> {
+        Grid::parse(&["abc", "def"], Some).unwrap()
+    }
+
+    #[test]
+    fn views_render_each_symmetry() {
+        let grid = build_grid();
+        assert_eq!(grid.view(Transform::Identity).render(|c| *c), "abc\ndef\n");
+        assert_eq!(grid.view(Transform::RotateClockwise).render(|c| *c), "da\neb\nfc\n");
+        assert_eq!(grid.view(Transform::Rotate180).render(|c| *c), "fed\ncba\n");
+        assert_eq!(grid.view(Transform::RotateCounterClockwise).render(|c| *c), "cf\nbe\nad\n");
+        assert_eq!(grid.view(Transform::FlipHorizontal).render(|c| *c), "cba\nfed\n");
+        assert_eq!(grid.view(Transform::FlipVertical).render(|c| *c), "def\nabc\n");
+        assert_eq!(grid.view(Transform::Transpose).render(|c| *c), "ad\nbe\ncf\n");
+        assert_eq!(grid.view(Transform::AntiTranspose).render(|c| *c), "fc\neb\nda\n");
+    }
+
+    #[test]
+    fn view_dimensions_follow_axis_swaps() {
+        let grid = build_grid();
+        let view = grid.view(Transform::RotateClockwise);
+        assert_eq!((view.width(), view.height()), (2, 3));
+        assert_eq!(view.get(2, 0), None);
+        assert_eq!(view.get(1, 2), Some(&'c'));
+    }
+
+    #[test]
+    fn inverse_restores_original() {
+        let grid = build_grid();
+        for transform in Transform::ALL {
+            assert_eq!(grid.transformed(transform).transformed(transform.inverse()), grid);
+            assert_eq!(transform.then(transform.inverse()), Transform::Identity);
+        }
+    }
+
+    #[test]
+    fn then_matches_nested_views() {
+        let grid = build_grid();
+        for first in Transform::ALL {
+            for second in Transform::ALL {
+                let nested = grid.view(first).view(second).to_grid();
+                let composed = grid.transformed(first.then(second));
+                let materialized = grid.transformed(first).transformed(second);
+                assert_eq!(nested, composed);
+                assert_eq!(nested, materialized);
+            }
+        }
+    }
+
+    #[test]
+    fn rotating_four_times_is_identity() {
+        let rotate = Transform::RotateClockwise;
+        assert_eq!(rotate.then(rotate), Transform::Rotate180);
+        assert_eq!(rotate.then(rotate).then(rotate), Transform::RotateCounterClockwise);
+        assert_eq!(rotate.then(rotate).then(rotate).then(rotate), Transform::Identity);
+    }
+
+    #[test]
+    fn rows_and_columns_follow_transform() {
+        let grid = build_grid();
+        let view = grid.view(Transform::Transpose);
+        let rows: Vec<String> = view.rows().map(|r| r.collect()).collect();
+        let columns: Vec<String> = view.columns().map(|c| c.collect()).collect();
+        assert_eq!(rows, vec!["ad", "be", "cf"]);
+        assert_eq!(columns, vec!["abc", "def"]);
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+// Hands out dense u32 ids to labels in order of first sight. A label can be interned when it is
+// first referenced, before whatever defines it, and keeps the same id from then on.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    ids: HashMap<String, u32>,
+    labels: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Interner {
+        Interner::default()
+    }
+
+    pub fn intern(&mut self, label: &str) -> u32 {
+        if let Some(id) = self.ids.get(label) {
+            return *id;
+        }
+        let id = u32::try_from(self.labels.len()).expect("Interner ran out of u32 ids");
+        self.ids.insert(label.to_string(), id);
+        self.labels.push(label.to_string());
+        id
+    }
+
+    // Looks a label up without interning it
+    pub fn get(&self, label: &str) -> Option<u32> {
+        self.ids.get(label).copied()
+    }
+
+    pub fn resolve(&self, id: u32) -> &str {
+        self.try_resolve(id).unwrap_or_else(|| panic!("Unknown interned id {}", id))
+    }
+
+    pub fn try_resolve(&self, id: u32) -> Option<&str> {
+        self.labels.get(id as usize).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    // Every (id, label) in id order
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+        (0..).zip(self.labels.iter().map(String::as_str))
+    }
+}
+
+#[cfg(test)]
+mod intern_tests {
+    use super::*;
+
+    #[test]
+    fn intern_assigns_dense_ids_in_first_seen_order() {
+        let mut interner = Interner::new();
+        assert_eq!(interner.intern("AAA"), 0);
+        assert_eq!(interner.intern("BBB"), 1);
+        assert_eq!(interner.intern("AAA"), 0);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.resolve(1), "BBB");
+        assert_eq!(interner.try_resolve(2), None);
+    }
+
+    #[test]
+    fn forward_references_keep_their_id_once_defined() {
+        let mut interner = Interner::new();
+        // "AAA = (BBB, CCC)" mentions BBB and CCC before their own lines
+        let ids: Vec<u32> = ["AAA", "BBB", "CCC"].iter().map(|l| interner.intern(l)).collect();
+        assert_eq!(interner.intern("CCC"), ids[2]);
+        assert_eq!(interner.get("BBB"), Some(1));
+        assert_eq!(interner.get("ZZZ"), None);
+        assert_eq!(interner.len(), 3);
+    }
+
+    #[test]
+    fn iter_lists_labels_by_id() {
+        let mut interner = Interner::new();
+        interner.intern("x");
+        interner.intern("y");
+        assert_eq!(interner.iter().collect::<Vec<(u32, &str)>>(), vec![(0, "x"), (1, "y")]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn resolve_when_unknown_panics() {
+        Interner::new().resolve(0);
+    }
+}
@@ -1,6 +1,30 @@
 use std::io::BufRead;
 
+// Lets the derive macros refer to ::common paths from inside this crate too
+extern crate self as common;
+
+pub use common_derive::{AocParse, TileChar};
+
+pub mod bitgrid;
+pub mod bitset;
+pub mod combi;
+pub mod compress;
+pub mod counter;
+pub mod cycle;
+pub mod dsu;
+pub mod geom;
+pub mod graph;
+pub mod grid;
+pub mod intern;
+pub mod math;
+pub mod memo;
+pub mod parse;
+pub mod poly;
+pub mod polygon;
 pub mod range;
+pub mod search;
+// Only holds the TileChar derive tests, the derive itself lives in common_derive
+mod tile;
 
 pub fn get_input_file_path() -> String {
     let args = std::env::args().collect::<Vec<String>>();
@@ -0,0 +1,487 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Rem, Sub, SubAssign};
+
+pub mod linear;
+pub mod rational;
+
+pub use linear::LinearSolution;
+pub use rational::Rational;
+
+pub trait Integer: Copy + PartialEq + PartialOrd
+    + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self>
+    + Div<Output = Self> + Rem<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    // None for the minimum of a signed type, whose magnitude does not fit
+    fn checked_abs_value(self) -> Option<Self>;
+    fn checked_multiply(self, other: Self) -> Option<Self>;
+    // Works on the unsigned magnitudes, so only a result that does not fit gives None
+    fn checked_gcd(self, other: Self) -> Option<Self>;
+}
+
+fn gcd_of_magnitudes<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        let remainder = a % b;
+        a = b;
+        b = remainder;
+    }
+    a
+}
+
+macro_rules! impl_integer_unsigned {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            fn checked_abs_value(self) -> Option<Self> { Some(self) }
+            fn checked_multiply(self, other: Self) -> Option<Self> { self.checked_mul(other) }
+            fn checked_gcd(self, other: Self) -> Option<Self> { Some(gcd_of_magnitudes(self, other)) }
+        }
+    )*};
+}
+
+macro_rules! impl_integer_signed {
+    ($($t:ty => $u:ty),*) => {$(
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            fn checked_abs_value(self) -> Option<Self> { self.checked_abs() }
+            fn checked_multiply(self, other: Self) -> Option<Self> { self.checked_mul(other) }
+            fn checked_gcd(self, other: Self) -> Option<Self> {
+                <$t>::try_from(gcd_of_magnitudes(self.unsigned_abs(), other.unsigned_abs())).ok()
+            }
+        }
+    )*};
+}
+
+impl_integer_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_integer_signed!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+// Always non-negative, gcd(0, 0) is 0. Only panics when the result itself does not fit, which
+// needs both values to be zero or the minimum of a signed type.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    a.checked_gcd(b).expect("gcd does not fit in the type")
+}
+
+// Divides before multiplying so only a result that truly does not fit can overflow
+pub fn checked_lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a.checked_abs_value()? / a.checked_gcd(b)?).checked_multiply(b.checked_abs_value()?)
+}
+
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    checked_lcm(a, b).expect("lcm overflowed")
+}
+
+pub fn checked_lcm_all<T: Integer, I: IntoIterator<Item = T>>(values: I) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, checked_lcm)
+}
+
+pub fn lcm_u128<I: IntoIterator<Item = u128>>(values: I) -> Option<u128> {
+    checked_lcm_all(values)
+}
+
+// Returns (g, x, y) with a * x + b * y = g = gcd(a, b)
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (1, 0);
+    let (mut old_t, mut t) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+        (old_t, t) = (t, old_t - quotient * t);
+    }
+    if old_r < 0 {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+pub fn mod_floor(a: i128, modulus: i128) -> i128 {
+    a.rem_euclid(modulus)
+}
+
+// Multiplies without overflowing for any positive modulus, even one close to i128::MAX
+pub fn mul_mod(a: i128, b: i128, modulus: i128) -> i128 {
+    let mut a = mod_floor(a, modulus);
+    let mut b = mod_floor(b, modulus);
+    if let Some(product) = a.checked_mul(b) {
+        return product % modulus;
+    }
+    // Both operands are below the modulus, so x + y - modulus cannot overflow
+    let add_mod = |x: i128, y: i128| if x >= modulus - y { x - (modulus - y) } else { x + y };
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a);
+        }
+        a = add_mod(a, a);
+        b >>= 1;
+    }
+    result
+}
+
+pub fn mod_pow(base: i128, exponent: u128, modulus: i128) -> i128 {
+    let mut result = 1 % modulus;
+    let mut base = mod_floor(base, modulus);
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    result
+}
+
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(mod_floor(a, modulus), modulus);
+    if g != 1 {
+        return None;
+    }
+    Some(mod_floor(x, modulus))
+}
+
+// Solves x = residue (mod modulus) for every pair, the moduli need not be coprime. Returns the
+// smallest non-negative solution and the combined modulus, or None when the system conflicts.
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut result: (i128, i128) = (0, 1);
+    for (residue, modulus) in congruences {
+        let (r1, m1) = result;
+        let (r2, m2) = (mod_floor(*residue, *modulus), *modulus);
+        let (g, p, _) = extended_gcd(m1, m2);
+        let difference = r2 - r1;
+        if difference % g != 0 {
+            return None;
+        }
+        let m2_reduced = m2 / g;
+        let combined = m1.checked_mul(m2_reduced)?;
+        let k = mul_mod(difference / g, p, m2_reduced);
+        result = (mod_floor(r1 + mul_mod(m1, k, combined), combined), combined);
+    }
+    Some(result)
+}
+
+// Floor of the square root, exact for the whole u128 range unlike going through f64
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method descends monotonically when started above the root
+    let mut x: u128 = 1 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+pub fn exact_sqrt(n: u128) -> Option<u128> {
+    let root = isqrt(n);
+    (root * root == n).then_some(root)
+}
+
+fn discriminant(a: i128, b: i128, c: i128) -> i128 {
+    b.checked_mul(b)
+        .and_then(|square| square.checked_sub(a.checked_mul(c)?.checked_mul(4)?))
+        .expect("Quadratic discriminant overflowed")
+}
+
+// Roots of a * x^2 + b * x + c = 0 in increasing order, only when they are rational. A double
+// root is listed once, an empty result means the roots are irrational or complex.
+pub fn rational_quadratic_roots(a: i128, b: i128, c: i128) -> Vec<Rational> {
+    assert!(a != 0, "Not a quadratic");
+    let discriminant = discriminant(a, b, c);
+    if discriminant < 0 {
+        return Vec::new();
+    }
+    let Some(root) = exact_sqrt(discriminant as u128) else {
+        return Vec::new();
+    };
+    let root = root as i128;
+    let mut roots = vec![Rational::new(-b - root, 2 * a), Rational::new(-b + root, 2 * a)];
+    roots.sort();
+    roots.dedup();
+    roots
+}
+
+// The inclusive range of integers x with a * x^2 + b * x + c <= 0 for a > 0, computed without
+// floating point so it stays exact on exact roots and large inputs
+pub fn quadratic_nonpositive_range(a: i128, b: i128, c: i128) -> Option<(i128, i128)> {
+    assert!(a > 0, "The parabola must open upwards");
+    let discriminant = discriminant(a, b, c);
+    if discriminant < 0 {
+        return None;
+    }
+    let root = isqrt(discriminant as u128) as i128;
+    let value = |x: i128| a * x * x + b * x + c;
+    // The integer square root is off by less than one, so each estimate is off by at most one
+    let low_estimate = -(b + root).div_euclid(2 * a);
+    let high_estimate = (root - b).div_euclid(2 * a);
+    let low = (low_estimate - 1..=low_estimate + 1).find(|x| value(*x) <= 0)?;
+    let high = (high_estimate - 1..=high_estimate + 1).rev().find(|x| value(*x) <= 0)?;
+    Some((low, high))
+}
+
+// Integer modulo a compile time modulus, which must be prime for division
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ModInt<const M: u64> {
+    value: u64,
+}
+
+impl<const M: u64> ModInt<M> {
+    pub fn new(value: i128) -> ModInt<M> {
+        ModInt { value: mod_floor(value, M as i128) as u64 }
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    pub fn pow(&self, exponent: u64) -> ModInt<M> {
+        ModInt { value: mod_pow(self.value as i128, exponent as u128, M as i128) as u64 }
+    }
+
+    pub fn inverse(&self) -> Option<ModInt<M>> {
+        mod_inverse(self.value as i128, M as i128).map(|v| ModInt { value: v as u64 })
+    }
+}
+
+impl<const M: u64> From<u64> for ModInt<M> {
+    fn from(value: u64) -> ModInt<M> {
+        ModInt::new(value as i128)
+    }
+}
+
+impl<const M: u64> From<i64> for ModInt<M> {
+    fn from(value: i64) -> ModInt<M> {
+        ModInt::new(value as i128)
+    }
+}
+
+impl<const M: u64> fmt::Display for ModInt<M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl<const M: u64> Add for ModInt<M> {
+    type Output = ModInt<M>;
+    fn add(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt::new(self.value as i128 + other.value as i128)
+    }
+}
+
+impl<const M: u64> Sub for ModInt<M> {
+    type Output = ModInt<M>;
+    fn sub(self, other: ModInt<M>) -> ModInt<M> {
+        ModInt::new(self.value as i128 - other.value as i128)
+    }
+}
+
+impl<const M: u64> Mul for ModInt<M> {
+    type Output = ModInt<M>;
+    fn mul(self, other: ModInt<M>) -> ModInt<M> {
+        // Two values below any u64 modulus always multiply within u128
+        ModInt { value: ((self.value as u128 * other.value as u128) % M as u128) as u64 }
+    }
+}
+
+impl<const M: u64> Div for ModInt<M> {
+    type Output = ModInt<M>;
+    fn div(self, other: ModInt<M>) -> ModInt<M> {
+        let inverse = other.inverse().expect("Division by a value with no modular inverse");
+        ModInt::new(mul_mod(self.value as i128, inverse.value as i128, M as i128))
+    }
+}
+
+impl<const M: u64> Neg for ModInt<M> {
+    type Output = ModInt<M>;
+    fn neg(self) -> ModInt<M> {
+        ModInt::new(-(self.value as i128))
+    }
+}
+
+impl<const M: u64> AddAssign for ModInt<M> {
+    fn add_assign(&mut self, other: ModInt<M>) {
+        *self = *self + other;
+    }
+}
+
+impl<const M: u64> SubAssign for ModInt<M> {
+    fn sub_assign(&mut self, other: ModInt<M>) {
+        *self = *self - other;
+    }
+}
+
+impl<const M: u64> MulAssign for ModInt<M> {
+    fn mul_assign(&mut self, other: ModInt<M>) {
+        *self = *self * other;
+    }
+}
+
+#[cfg(test)]
+mod number_theory_tests {
+    use super::*;
+
+    #[test]
+    fn gcd_handles_zero_and_signs() {
+        assert_eq!(gcd(12u32, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0usize, 5), 5);
+        assert_eq!(gcd(0u8, 0), 0);
+    }
+
+    #[test]
+    fn lcm_does_not_overflow_intermediate_product() {
+        // a * b overflows u64, but the lcm itself fits
+        let a: u64 = 1 << 40;
+        let b: u64 = 3 << 40;
+        assert_eq!(lcm(a, b), 3 << 40);
+        assert_eq!(checked_lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(checked_lcm(0u64, 5), Some(0));
+    }
+
+    #[test]
+    fn lcm_all_combines_every_value() {
+        assert_eq!(checked_lcm_all(vec![2usize, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_u128(vec![u64::MAX as u128, 2]), Some(u64::MAX as u128 * 2));
+        assert_eq!(checked_lcm_all(vec![u8::MAX, 2]), None);
+    }
+
+    #[test]
+    fn extended_gcd_satisfies_bezout() {
+        for (a, b) in [(240, 46), (46, 240), (-35, 15), (17, 0), (0, 9)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn mod_inverse_when_not_coprime_returns_none() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn mul_mod_handles_large_operands() {
+        let modulus = (1i128 << 100) + 7;
+        let a = modulus - 1;
+        assert_eq!(mul_mod(a, a, modulus), 1);
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+    }
+
+    #[test]
+    fn crt_with_coprime_moduli() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+    }
+
+    #[test]
+    fn crt_with_shared_factors() {
+        // x = 3 mod 4 and x = 5 mod 6 gives x = 11 mod 12
+        assert_eq!(crt(&[(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn crt_with_offset_cycles() {
+        // Cycles that do not start at zero: hits at 5 + 7k and 2 + 9k
+        let (x, m) = crt(&[(5, 7), (2, 9)]).unwrap();
+        assert_eq!(m, 63);
+        assert_eq!(x % 7, 5);
+        assert_eq!(x % 9, 2);
+    }
+
+    #[test]
+    fn mod_int_arithmetic() {
+        type M = ModInt<1_000_000_007>;
+        let a = M::from(1_000_000_006u64);
+        let b = M::from(5u64);
+        assert_eq!((a + b).value(), 4);
+        assert_eq!((b - a).value(), 6);
+        assert_eq!((a * a).value(), 1);
+        assert_eq!((-b).value(), 1_000_000_002);
+        assert_eq!((b / b).value(), 1);
+        assert_eq!(M::from(-1i64), a);
+        assert_eq!(M::from(2u64).pow(30).value(), (1u64 << 30) % 1_000_000_007);
+        assert_eq!(ModInt::<10>::from(4u64).inverse(), None);
+    }
+
+    #[test]
+    fn isqrt_is_exact_near_perfect_squares() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        let big = (1u128 << 62) + 12_345;
+        assert_eq!(isqrt(big * big), big);
+        assert_eq!(isqrt(big * big - 1), big - 1);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        assert_eq!(exact_sqrt(49), Some(7));
+        assert_eq!(exact_sqrt(50), None);
+    }
+
+    #[test]
+    fn rational_quadratic_roots_when_discriminant_is_square() {
+        // 2x^2 - x - 3 = (2x - 3)(x + 1)
+        assert_eq!(rational_quadratic_roots(2, -1, -3), vec![Rational::integer(-1), Rational::new(3, 2)]);
+        assert_eq!(rational_quadratic_roots(1, -4, 4), vec![Rational::integer(2)]);
+        assert_eq!(rational_quadratic_roots(1, 0, -2), vec![]);
+        assert_eq!(rational_quadratic_roots(1, 0, 1), vec![]);
+    }
+
+    #[test]
+    fn quadratic_nonpositive_range_includes_exact_roots() {
+        // x^2 - 30x + 200 = (x - 10)(x - 20)
+        assert_eq!(quadratic_nonpositive_range(1, -30, 200), Some((10, 20)));
+        assert_eq!(quadratic_nonpositive_range(1, -30, 201), Some((11, 19)));
+        assert_eq!(quadratic_nonpositive_range(1, 0, 0), Some((0, 0)));
+        // Between 0.4 and 0.6 there is no integer
+        assert_eq!(quadratic_nonpositive_range(100, -100, 24), None);
+        assert_eq!(quadratic_nonpositive_range(1, 0, 1), None);
+    }
+
+    #[test]
+    fn quadratic_nonpositive_range_with_large_values() {
+        // Roots at 10^9 and 10^9 + 1, far beyond what f64 separates after squaring
+        let r = 1_000_000_000i128;
+        assert_eq!(quadratic_nonpositive_range(1, -(2 * r + 1), r * (r + 1)), Some((r, r + 1)));
+    }
+
+    #[test]
+    fn gcd_and_lcm_at_signed_minimum() {
+        assert_eq!(gcd(i128::MIN, 6), 2);
+        assert_eq!(gcd(i64::MIN, i64::MIN + 1), 1);
+        assert_eq!(i8::MIN.checked_gcd(0), None);
+        assert_eq!(checked_lcm(i64::MIN, 3), None);
+        assert_eq!(checked_lcm(i64::MIN / 2, 2), Some(i64::MAX / 2 + 1));
+    }
+
+    #[test]
+    fn mul_mod_near_i128_max() {
+        let modulus = i128::MAX - 1;
+        assert_eq!(mul_mod(modulus - 1, modulus - 1, modulus), 1);
+        assert_eq!(mul_mod(modulus - 1, 2, modulus), modulus - 2);
+    }
+
+    #[test]
+    fn mod_int_with_modulus_above_i64() {
+        // The largest u64 prime, where products of residues overflow i128
+        type M = ModInt<18_446_744_073_709_551_557>;
+        let minus_one = M::from(-1i64);
+        assert_eq!((minus_one * minus_one).value(), 1);
+        assert_eq!((M::from(2u64).pow(64) * M::from(2u64).inverse().unwrap()).value(), (1u128 << 63) as u64);
+        assert_eq!((minus_one / minus_one).value(), 1);
+    }
+}
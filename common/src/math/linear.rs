@@ -0,0 +1,123 @@
+use super::rational::Rational;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinearSolution {
+    Unique(Vec<Rational>),
+    NoSolution,
+    // One solution with every free variable set to zero, plus the variables that may vary
+    Infinite { particular: Vec<Rational>, free_variables: Vec<usize> },
+}
+
+// Solves coefficients * x = constants by Gauss-Jordan elimination over exact rationals. The
+// matrix may have any shape, every row must have the same number of columns.
+pub fn solve(coefficients: &[Vec<Rational>], constants: &[Rational]) -> LinearSolution {
+    assert_eq!(coefficients.len(), constants.len(), "Need one constant per equation");
+    let variables = coefficients.first().map_or(0, Vec::len);
+    let mut rows: Vec<Vec<Rational>> = coefficients.iter().zip(constants)
+        .map(|(row, constant)| {
+            assert_eq!(row.len(), variables, "Every equation needs the same number of coefficients");
+            row.iter().copied().chain([*constant]).collect()
+        })
+        .collect();
+
+    let mut pivot_columns: Vec<usize> = Vec::new();
+    for column in 0..variables {
+        let pivot_row = pivot_columns.len();
+        let Some(found) = (pivot_row..rows.len()).find(|r| !rows[*r][column].is_zero()) else {
+            continue;
+        };
+        rows.swap(pivot_row, found);
+        let pivot = rows[pivot_row][column];
+        for value in rows[pivot_row].iter_mut() {
+            *value /= pivot;
+        }
+        let pivot_values = rows[pivot_row].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[column];
+            if r == pivot_row || factor.is_zero() {
+                continue;
+            }
+            for (value, pivot_value) in row.iter_mut().zip(&pivot_values).skip(column) {
+                *value -= factor * *pivot_value;
+            }
+        }
+        pivot_columns.push(column);
+    }
+
+    // A leftover row reading 0 = c with c non-zero means the equations contradict each other
+    if rows[pivot_columns.len()..].iter().any(|row| !row[variables].is_zero()) {
+        return LinearSolution::NoSolution;
+    }
+    let mut particular = vec![Rational::ZERO; variables];
+    for (row, column) in pivot_columns.iter().enumerate() {
+        particular[*column] = rows[row][variables];
+    }
+    if pivot_columns.len() == variables {
+        LinearSolution::Unique(particular)
+    } else {
+        let free_variables = (0..variables).filter(|c| !pivot_columns.contains(c)).collect();
+        LinearSolution::Infinite { particular, free_variables }
+    }
+}
+
+pub fn solve_integers(coefficients: &[Vec<i128>], constants: &[i128]) -> LinearSolution {
+    let coefficients: Vec<Vec<Rational>> = coefficients.iter()
+        .map(|row| row.iter().map(|v| Rational::integer(*v)).collect())
+        .collect();
+    let constants: Vec<Rational> = constants.iter().map(|v| Rational::integer(*v)).collect();
+    solve(&coefficients, &constants)
+}
+
+#[cfg(test)]
+mod linear_tests {
+    use super::*;
+
+    fn integers(values: &[i128]) -> Vec<Rational> {
+        values.iter().map(|v| Rational::integer(*v)).collect()
+    }
+
+    #[test]
+    fn solve_when_unique_returns_exact_values() {
+        // x + y + z = 6, 2y + 5z = -4, 2x + 5y - z = 27
+        let solution = solve_integers(&[vec![1, 1, 1], vec![0, 2, 5], vec![2, 5, -1]], &[6, -4, 27]);
+        assert_eq!(solution, LinearSolution::Unique(integers(&[5, 3, -2])));
+    }
+
+    #[test]
+    fn solve_keeps_fractions() {
+        // 3x = 1, 2x + 4y = 2
+        let solution = solve_integers(&[vec![3, 0], vec![2, 4]], &[1, 2]);
+        assert_eq!(solution, LinearSolution::Unique(vec![Rational::new(1, 3), Rational::new(1, 3)]));
+    }
+
+    #[test]
+    fn solve_when_inconsistent_returns_no_solution() {
+        let solution = solve_integers(&[vec![1, 2], vec![2, 4]], &[3, 7]);
+        assert_eq!(solution, LinearSolution::NoSolution);
+    }
+
+    #[test]
+    fn solve_when_underdetermined_returns_free_variables() {
+        // x + 2y - z = 4 and 2x + 4y = 10 leave y free
+        let solution = solve_integers(&[vec![1, 2, -1], vec![2, 4, 0]], &[4, 10]);
+        assert_eq!(solution, LinearSolution::Infinite {
+            particular: integers(&[5, 0, 1]),
+            free_variables: vec![1],
+        });
+    }
+
+    #[test]
+    fn solve_with_redundant_equations() {
+        // The third equation is the sum of the first two
+        let solution = solve_integers(&[vec![1, 1], vec![1, -1], vec![2, 0]], &[10, 2, 12]);
+        assert_eq!(solution, LinearSolution::Unique(integers(&[6, 4])));
+    }
+
+    #[test]
+    fn solve_with_large_coefficients() {
+        // Hailstone sized values whose products do not fit in i64
+        let a = 19_000_000_000_000i128;
+        let solution = solve_integers(&[vec![a, 1], vec![a + 3, -2]], &[a * 7 + 5, (a + 3) * 7 - 10]);
+        assert_eq!(solution, LinearSolution::Unique(integers(&[7, 5])));
+    }
+}
@@ -0,0 +1,310 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use super::{gcd, Integer};
+
+// Exact fraction, always kept in lowest terms with a positive denominator so derived equality
+// and hashing are by value. The operators panic on i128 overflow, the checked_ methods do not.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numer: i128,
+    denom: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { numer: 0, denom: 1 };
+    pub const ONE: Rational = Rational { numer: 1, denom: 1 };
+
+    pub fn new(numer: i128, denom: i128) -> Rational {
+        Rational::checked_new(numer, denom).expect("Rational with a zero denominator or overflow")
+    }
+
+    pub fn checked_new(numer: i128, denom: i128) -> Option<Rational> {
+        if denom == 0 {
+            return None;
+        }
+        let divisor = numer.checked_gcd(denom)?;
+        let (numer, denom) = (numer / divisor, denom / divisor);
+        if denom < 0 {
+            Some(Rational { numer: numer.checked_neg()?, denom: denom.checked_neg()? })
+        } else {
+            Some(Rational { numer, denom })
+        }
+    }
+
+    pub fn integer(value: i128) -> Rational {
+        Rational { numer: value, denom: 1 }
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.numer
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.denom
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numer == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.numer)
+    }
+
+    pub fn floor(&self) -> i128 {
+        self.numer.div_euclid(self.denom)
+    }
+
+    pub fn ceil(&self) -> i128 {
+        -(-self.numer).div_euclid(self.denom)
+    }
+
+    pub fn abs(&self) -> Rational {
+        Rational { numer: self.numer.checked_abs().expect("Rational abs overflowed"), denom: self.denom }
+    }
+
+    pub fn recip(&self) -> Option<Rational> {
+        Rational::checked_new(self.denom, self.numer)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.numer as f64 / self.denom as f64
+    }
+
+    // Cross-reduces before multiplying so intermediate products stay as small as possible
+    pub fn checked_add(&self, other: &Rational) -> Option<Rational> {
+        let divisor = gcd(self.denom, other.denom);
+        let (left, right) = (self.denom / divisor, other.denom / divisor);
+        let numer = self.numer.checked_mul(right)?.checked_add(other.numer.checked_mul(left)?)?;
+        Rational::checked_new(numer, self.denom.checked_mul(right)?)
+    }
+
+    pub fn checked_sub(&self, other: &Rational) -> Option<Rational> {
+        self.checked_add(&other.checked_neg()?)
+    }
+
+    pub fn checked_mul(&self, other: &Rational) -> Option<Rational> {
+        let first = gcd(self.numer, other.denom).max(1);
+        let second = gcd(other.numer, self.denom).max(1);
+        let numer = (self.numer / first).checked_mul(other.numer / second)?;
+        let denom = (self.denom / second).checked_mul(other.denom / first)?;
+        Rational::checked_new(numer, denom)
+    }
+
+    pub fn checked_div(&self, other: &Rational) -> Option<Rational> {
+        self.checked_mul(&other.recip()?)
+    }
+
+    pub fn checked_neg(&self) -> Option<Rational> {
+        Some(Rational { numer: self.numer.checked_neg()?, denom: self.denom })
+    }
+}
+
+impl Default for Rational {
+    fn default() -> Self {
+        Rational::ZERO
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Rational {
+        Rational::integer(value as i128)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Rational {
+        Rational::integer(value)
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        match (self.numer.checked_mul(other.denom), other.numer.checked_mul(self.denom)) {
+            (Some(left), Some(right)) => left.cmp(&right),
+            _ => compare_fractions(self.numer, self.denom, other.numer, other.denom),
+        }
+    }
+}
+
+// Compares a / b with c / d (b, d > 0) through their continued fraction expansions, which never
+// needs a product larger than the inputs
+fn compare_fractions(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    let (mut a, mut b, mut c, mut d) = (a, b, c, d);
+    loop {
+        let (left, right) = (a.div_euclid(b), c.div_euclid(d));
+        if left != right {
+            return left.cmp(&right);
+        }
+        let (left_rest, right_rest) = (a.rem_euclid(b), c.rem_euclid(d));
+        match (left_rest == 0, right_rest == 0) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            // a / b < c / d exactly when d / right_rest < b / left_rest
+            (false, false) => (a, b, c, d) = (d, right_rest, b, left_rest),
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.denom == 1 {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl FromStr for Rational {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Rational, String> {
+        let invalid = || format!("Invalid rational {:?}", s);
+        match s.split_once('/') {
+            Some((numer, denom)) => {
+                let numer = numer.trim().parse::<i128>().map_err(|_| invalid())?;
+                let denom = denom.trim().parse::<i128>().map_err(|_| invalid())?;
+                Rational::checked_new(numer, denom).ok_or_else(invalid)
+            }
+            None => s.trim().parse::<i128>().map(Rational::integer).map_err(|_| invalid()),
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+    fn add(self, other: Rational) -> Rational {
+        self.checked_add(&other).expect("Rational addition overflowed")
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+    fn sub(self, other: Rational) -> Rational {
+        self.checked_sub(&other).expect("Rational subtraction overflowed")
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+    fn mul(self, other: Rational) -> Rational {
+        self.checked_mul(&other).expect("Rational multiplication overflowed")
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+    fn div(self, other: Rational) -> Rational {
+        assert!(!other.is_zero(), "Rational division by zero");
+        self.checked_div(&other).expect("Rational division overflowed")
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+    fn neg(self) -> Rational {
+        self.checked_neg().expect("Rational negation overflowed")
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, other: Rational) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, other: Rational) {
+        *self = *self - other;
+    }
+}
+
+impl MulAssign for Rational {
+    fn mul_assign(&mut self, other: Rational) {
+        *self = *self * other;
+    }
+}
+
+impl DivAssign for Rational {
+    fn div_assign(&mut self, other: Rational) {
+        *self = *self / other;
+    }
+}
+
+#[cfg(test)]
+mod rational_tests {
+    use super::*;
+
+    #[test]
+    fn new_reduces_and_normalizes_sign() {
+        let value = Rational::new(6, -8);
+        assert_eq!((value.numer(), value.denom()), (-3, 4));
+        assert_eq!(Rational::new(0, -5), Rational::ZERO);
+        assert_eq!(Rational::checked_new(1, 0), None);
+        assert_eq!(value.to_string(), "-3/4");
+        assert_eq!(Rational::new(10, 5).to_string(), "2");
+    }
+
+    #[test]
+    fn arithmetic_is_exact() {
+        let third = Rational::new(1, 3);
+        let sixth = Rational::new(1, 6);
+        assert_eq!(third + sixth, Rational::new(1, 2));
+        assert_eq!(third - sixth, sixth);
+        assert_eq!(third * sixth, Rational::new(1, 18));
+        assert_eq!(third / sixth, Rational::integer(2));
+        assert_eq!(-third, Rational::new(-1, 3));
+        assert_eq!((third + third + third).to_integer(), Some(1));
+    }
+
+    #[test]
+    fn floor_and_ceil_round_towards_infinities() {
+        assert_eq!(Rational::new(7, 2).floor(), 3);
+        assert_eq!(Rational::new(7, 2).ceil(), 4);
+        assert_eq!(Rational::new(-7, 2).floor(), -4);
+        assert_eq!(Rational::new(-7, 2).ceil(), -3);
+        assert_eq!(Rational::integer(-3).ceil(), -3);
+    }
+
+    #[test]
+    fn ordering_when_cross_product_overflows() {
+        let big = Rational::new(i128::MAX - 1, 3);
+        let bigger = Rational::new(i128::MAX, 3);
+        assert!(big < bigger);
+        assert!(Rational::new(-1, 3) < Rational::new(-1, 4));
+    }
+
+    #[test]
+    fn cross_reduction_avoids_spurious_overflow() {
+        let large = Rational::new(1 << 100, 3);
+        let shrink = Rational::new(3, 1 << 100);
+        assert_eq!(large * shrink, Rational::ONE);
+        assert_eq!(Rational::integer(i128::MAX).checked_add(&Rational::ONE), None);
+        assert_eq!(Rational::checked_new(i128::MIN, 2), Some(Rational::integer(i128::MIN / 2)));
+        assert_eq!(Rational::checked_new(i128::MIN, -1), None);
+        assert_eq!(Rational::checked_new(1, i128::MIN), None);
+    }
+
+    #[test]
+    fn from_str_accepts_fractions_and_integers() {
+        assert_eq!("-4/6".parse::<Rational>(), Ok(Rational::new(-2, 3)));
+        assert_eq!("12".parse::<Rational>(), Ok(Rational::integer(12)));
+        assert!("1/0".parse::<Rational>().is_err());
+        assert!("x".parse::<Rational>().is_err());
+    }
+}
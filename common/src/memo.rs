@@ -0,0 +1,113 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+// Cache for one tree of recursive calls. The recursive function takes the memo as a parameter
+// and wraps its body in get_or_insert_with, so the cache lives exactly as long as the caller
+// wants instead of being global. Keys should be small, such as indices into shared input.
+#[derive(Debug, Clone)]
+pub struct Memo<K: Hash + Eq, V: Clone> {
+    cache: HashMap<K, V>,
+    limit: Option<usize>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo { cache: HashMap::new(), limit: None, hits: 0, misses: 0 }
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Once limit entries are stored, further results are still computed but no longer cached
+    pub fn with_limit(limit: usize) -> Self {
+        Memo { limit: Some(limit), ..Self::default() }
+    }
+
+    pub fn get_or_insert_with(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.hits += 1;
+            return value.clone();
+        }
+        self.misses += 1;
+        let value = compute(self);
+        if self.limit.is_none_or(|limit| self.cache.len() < limit) {
+            self.cache.insert(key, value.clone());
+        }
+        value
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    // Drops the cached values and resets the statistics, keeping the size bound
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats { hits: self.hits, misses: self.misses, entries: self.cache.len() }
+    }
+}
+
+#[cfg(test)]
+mod memo_tests {
+    use super::*;
+
+    fn fibonacci(memo: &mut Memo<u32, u128>, n: u32) -> u128 {
+        if n < 2 {
+            return n as u128;
+        }
+        memo.get_or_insert_with(n, |memo| fibonacci(memo, n - 1) + fibonacci(memo, n - 2))
+    }
+
+    #[test]
+    fn recursive_calls_share_the_cache() {
+        let mut memo = Memo::new();
+        assert_eq!(fibonacci(&mut memo, 150), 9969216677189303386214405760200);
+        // Every n from 2 to 150 is computed once and looked up once more
+        assert_eq!(memo.stats(), MemoStats { hits: 147, misses: 149, entries: 149 });
+        assert_eq!(memo.get(&10), Some(&55));
+    }
+
+    #[test]
+    fn clear_resets_entries_and_stats() {
+        let mut memo = Memo::new();
+        fibonacci(&mut memo, 20);
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), MemoStats::default());
+        assert_eq!(fibonacci(&mut memo, 20), 6765);
+    }
+
+    #[test]
+    fn limit_bounds_entries_without_changing_results() {
+        let mut memo = Memo::with_limit(10);
+        assert_eq!(fibonacci(&mut memo, 30), 832040);
+        assert_eq!(memo.len(), 10);
+        // Values past the bound are recomputed whenever they are needed again
+        assert!(memo.stats().misses > 29);
+    }
+}
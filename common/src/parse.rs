@@ -0,0 +1,364 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    // Byte offset into the scanned input
+    pub position: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Expected {} at column {}, found {:?}", self.expected, self.position + 1, self.found)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+#[derive(Debug, Clone)]
+pub struct Scanner<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(input: &'a str) -> Scanner<'a> {
+        Scanner { input, position: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.position == self.input.len()
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    pub fn error<T>(&self, expected: &str) -> ParseResult<T> {
+        let found = match self.rest().split_whitespace().next() {
+            Some(word) => word.to_string(),
+            None if self.is_done() => "end of input".to_string(),
+            None => self.rest().to_string(),
+        };
+        Err(ParseError { position: self.position, expected: expected.to_string(), found })
+    }
+
+    pub fn take_while<F: FnMut(char) -> bool>(&mut self, mut predicate: F) -> &'a str {
+        let rest = self.rest();
+        let length = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.position += length;
+        &rest[..length]
+    }
+
+    pub fn ws(&mut self) -> &mut Self {
+        self.take_while(char::is_whitespace);
+        self
+    }
+
+    pub fn tag(&mut self, tag: &str) -> ParseResult<&mut Self> {
+        if !self.rest().starts_with(tag) {
+            return self.error(&format!("{:?}", tag));
+        }
+        self.position += tag.len();
+        Ok(self)
+    }
+
+    // Consumes the tag when present, leaving the scanner untouched otherwise
+    pub fn try_tag(&mut self, tag: &str) -> bool {
+        self.tag(tag).is_ok()
+    }
+
+    pub fn char(&mut self) -> ParseResult<char> {
+        match self.peek() {
+            Some(c) => {
+                self.position += c.len_utf8();
+                Ok(c)
+            }
+            None => self.error("a character"),
+        }
+    }
+
+    pub fn ident(&mut self) -> ParseResult<&'a str> {
+        let ident = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        if ident.is_empty() {
+            return self.error("an identifier");
+        }
+        Ok(ident)
+    }
+
+    pub fn int<T: FromStr>(&mut self) -> ParseResult<T> {
+        let start = self.position;
+        let rest = self.rest();
+        let sign = if rest.starts_with(['-', '+']) { 1 } else { 0 };
+        let digits = rest[sign..].find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len() - sign);
+        if digits == 0 {
+            return self.error("an integer");
+        }
+        match rest[..sign + digits].parse() {
+            Ok(value) => {
+                self.position = start + sign + digits;
+                Ok(value)
+            }
+            Err(_) => self.error("an integer in range"),
+        }
+    }
+
+    // Parses one or more items separated by sep, stopping before a separator that is not
+    // followed by a valid item
+    pub fn sep_by<T, F>(&mut self, sep: &str, mut item: F) -> ParseResult<Vec<T>>
+        where F: FnMut(&mut Scanner<'a>) -> ParseResult<T> {
+        let mut items = vec![item(self)?];
+        loop {
+            let before = self.position;
+            if !self.try_tag(sep) {
+                return Ok(items);
+            }
+            match item(self) {
+                Ok(value) => items.push(value),
+                Err(_) => {
+                    self.position = before;
+                    return Ok(items);
+                }
+            }
+        }
+    }
+
+    // Runs parser, rewinding and reporting what was expected if it fails
+    pub fn expect<T, F>(&mut self, expected: &str, parser: F) -> ParseResult<T>
+        where F: FnOnce(&mut Scanner<'a>) -> ParseResult<T> {
+        let start = self.position;
+        parser(self).or_else(|_| {
+            self.position = start;
+            self.error(expected)
+        })
+    }
+
+    // Runs parser for a named field, labelling errors raised before anything was consumed
+    pub fn field<T, F>(&mut self, name: &str, parser: F) -> ParseResult<T>
+        where F: FnOnce(&mut Scanner<'a>) -> ParseResult<T> {
+        let start = self.position;
+        parser(self).map_err(|mut e| {
+            if e.position == start {
+                e.expected = format!("{} for {}", e.expected, name);
+            }
+            e
+        })
+    }
+
+    pub fn end(&mut self) -> ParseResult<()> {
+        if !self.is_done() {
+            return self.error("end of input");
+        }
+        Ok(())
+    }
+}
+
+// Implemented by #[derive(AocParse)], parses a value from the front of the scanner
+pub trait Parse: Sized {
+    fn parse(scanner: &mut Scanner<'_>) -> ParseResult<Self>;
+}
+
+macro_rules! impl_parse_int {
+    ($($t:ty),*) => {$(
+        impl Parse for $t {
+            fn parse(scanner: &mut Scanner<'_>) -> ParseResult<Self> { scanner.int() }
+        }
+    )*};
+}
+
+impl_parse_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Parse for char {
+    fn parse(scanner: &mut Scanner<'_>) -> ParseResult<Self> {
+        scanner.char()
+    }
+}
+
+impl Parse for String {
+    fn parse(scanner: &mut Scanner<'_>) -> ParseResult<Self> {
+        scanner.ident().map(str::to_string)
+    }
+}
+
+pub fn parse_line<T: Parse>(line: &str) -> ParseResult<T> {
+    parse_all(line, T::parse)
+}
+
+// Runs parser over the whole input, failing if anything is left unconsumed
+pub fn parse_all<'a, T, F>(input: &'a str, parser: F) -> ParseResult<T>
+    where F: FnOnce(&mut Scanner<'a>) -> ParseResult<T> {
+    let mut scanner = Scanner::new(input);
+    let value = parser(&mut scanner)?;
+    scanner.end()?;
+    Ok(value)
+}
+
+// Pulls every integer out of the input, treating '-' as a sign only when it does not follow a
+// digit, so "3-5" gives 3 and 5 while "x=-5" gives -5. A number that does not fit T is an
+// error rather than being skipped.
+pub fn ints<T: FromStr>(input: &str) -> ParseResult<Vec<T>> {
+    let bytes = input.as_bytes();
+    let mut values = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let negative = bytes[i] == b'-'
+            && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+            && (i == 0 || !bytes[i - 1].is_ascii_digit());
+        if !negative && !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        i += 1;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        let token = &input[start..i];
+        match token.parse() {
+            Ok(value) => values.push(value),
+            Err(_) => return Err(ParseError {
+                position: start,
+                expected: "an integer in range".to_string(),
+                found: token.to_string(),
+            }),
+        }
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod parse_tests {
+    use super::*;
+
+    #[test]
+    fn tag_and_int_parse_in_sequence() {
+        let mut scanner = Scanner::new("Game 12: rest");
+        let id: u32 = scanner.tag("Game").unwrap().ws().int().unwrap();
+        scanner.tag(": ").unwrap();
+        assert_eq!(id, 12);
+        assert_eq!(scanner.rest(), "rest");
+    }
+
+    #[test]
+    fn tag_when_mismatched_reports_position() {
+        let mut scanner = Scanner::new("Card 1");
+        let error = scanner.tag("Game").err().unwrap();
+        assert_eq!(error.position, 0);
+        assert_eq!(error.found, "Card");
+        assert_eq!(error.to_string(), "Expected \"Game\" at column 1, found \"Card\"");
+    }
+
+    #[test]
+    fn int_handles_signs_and_overflow() {
+        assert_eq!(Scanner::new("-42x").int::<i64>(), Ok(-42));
+        assert_eq!(Scanner::new("+7").int::<i32>(), Ok(7));
+        assert!(Scanner::new("-").int::<i32>().is_err());
+        let mut scanner = Scanner::new("300");
+        assert_eq!(scanner.int::<u8>().unwrap_err().expected, "an integer in range");
+        assert_eq!(scanner.position(), 0);
+    }
+
+    #[test]
+    fn sep_by_collects_items() {
+        let mut scanner = Scanner::new("1, 2, 3; 4");
+        let values = scanner.sep_by(", ", |s| s.int::<u32>()).unwrap();
+        assert_eq!(values, vec![1, 2, 3]);
+        assert_eq!(scanner.rest(), "; 4");
+    }
+
+    #[test]
+    fn sep_by_leaves_trailing_separator() {
+        let mut scanner = Scanner::new("1,2,");
+        assert_eq!(scanner.sep_by(",", |s| s.int::<u32>()).unwrap(), vec![1, 2]);
+        assert_eq!(scanner.rest(), ",");
+    }
+
+    #[test]
+    fn ident_and_expect() {
+        let mut scanner = Scanner::new("AAA = (BBB, CCC)");
+        assert_eq!(scanner.ident(), Ok("AAA"));
+        scanner.ws().tag("= (").unwrap();
+        let error = scanner.expect("a node pair", |s| {
+            let left = s.ident()?;
+            s.tag(" ")?;
+            Ok(left)
+        }).unwrap_err();
+        assert_eq!(error.position, 7);
+        assert_eq!(error.expected, "a node pair");
+        assert_eq!(scanner.rest(), "BBB, CCC)");
+    }
+
+    #[test]
+    fn parse_all_requires_end() {
+        assert_eq!(parse_all("12", |s| s.int::<u32>()), Ok(12));
+        let error = parse_all("12 ", |s| s.int::<u32>()).unwrap_err();
+        assert_eq!((error.position, error.expected.as_str()), (2, "end of input"));
+    }
+
+    #[test]
+    fn ints_extracts_signed_values() {
+        assert_eq!(ints::<i64>("Time:      7  15   30"), Ok(vec![7, 15, 30]));
+        assert_eq!(ints::<i64>("x=-5, y=3-4"), Ok(vec![-5, 3, 4]));
+        assert_eq!(ints::<u32>("Card   3: 41 48 | 83"), Ok(vec![3, 41, 48, 83]));
+        assert_eq!(ints::<i32>("no numbers"), Ok(vec![]));
+    }
+
+    #[test]
+    fn ints_when_value_does_not_fit_returns_error() {
+        let error = ints::<u8>("1 300 2").unwrap_err();
+        assert_eq!((error.position, error.found.as_str()), (2, "300"));
+        assert_eq!(error.to_string(), "Expected an integer in range at column 3, found \"300\"");
+        assert_eq!(ints::<u32>("x=-5").unwrap_err().found, "-5");
+    }
+
+    #[derive(Debug, PartialEq, crate::AocParse)]
+    #[aoc(format = "{dest} {src} {len}")]
+    struct Mapping {
+        dest: i64,
+        src: i64,
+        len: i64,
+    }
+
+    #[derive(Debug, PartialEq, crate::AocParse)]
+    #[aoc(format = "Game {id}: {rounds:sep('; ')} [{{{tag}}}]")]
+    struct Game {
+        id: u32,
+        rounds: Vec<Mapping>,
+        tag: String,
+    }
+
+    #[test]
+    fn derived_parse_reads_fields() {
+        let mapping: Mapping = parse_line("50 98  2").unwrap();
+        assert_eq!(mapping, Mapping { dest: 50, src: 98, len: 2 });
+    }
+
+    #[test]
+    fn derived_parse_nests_and_separates() {
+        let game: Game = parse_line("Game 3: 1 2 3; 4 5 6 [{abc}]").unwrap();
+        assert_eq!(game.id, 3);
+        assert_eq!(game.rounds, vec![Mapping { dest: 1, src: 2, len: 3 }, Mapping { dest: 4, src: 5, len: 6 }]);
+        assert_eq!(game.tag, "abc");
+    }
+
+    #[test]
+    fn derived_parse_when_field_invalid_names_it() {
+        let error = parse_line::<Mapping>("50 x 2").unwrap_err();
+        assert_eq!(error.position, 3);
+        assert_eq!(error.to_string(), "Expected an integer for Mapping.src at column 4, found \"x\"");
+        let error = parse_line::<Game>("Round 3: 1 2 3 [{a}]").unwrap_err();
+        assert_eq!(error.expected, "\"Game\"");
+    }
+}
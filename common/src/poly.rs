@@ -0,0 +1,202 @@
+use std::fmt;
+
+pub use num_bigint::BigInt;
+use num_traits::{One, Zero};
+
+use crate::math::{linear, LinearSolution, Rational};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PolyError {
+    Empty,
+    // The differences past the degree bound were not all zero
+    NotPolynomial { max_degree: usize },
+    DuplicatePoint(i64),
+    NotAnInteger,
+}
+
+impl fmt::Display for PolyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PolyError::Empty => write!(f, "Cannot extrapolate an empty sequence"),
+            PolyError::NotPolynomial { max_degree } => {
+                write!(f, "Sequence is not a polynomial of degree {} or less", max_degree)
+            }
+            PolyError::DuplicatePoint(x) => write!(f, "Two points share x = {}", x),
+            PolyError::NotAnInteger => write!(f, "Interpolated value is not an integer"),
+        }
+    }
+}
+
+impl std::error::Error for PolyError {}
+
+fn to_big<T: Clone + Into<BigInt>>(values: &[T]) -> Vec<BigInt> {
+    values.iter().cloned().map(Into::into).collect()
+}
+
+// The first value of every difference row, stopping before the first row that is all zeros.
+// Its length is the degree plus one, or zero for the zero sequence. When there are no more
+// values than max_degree + 1 the polynomial is trusted to fit, as the values fix it exactly.
+fn leading_differences(values: &[BigInt], max_degree: usize) -> Result<Vec<BigInt>, PolyError> {
+    if values.is_empty() {
+        return Err(PolyError::Empty);
+    }
+    let mut row = values.to_vec();
+    let mut leading = Vec::new();
+    loop {
+        if row.iter().all(Zero::is_zero) {
+            return Ok(leading);
+        }
+        if leading.len() > max_degree {
+            return Err(PolyError::NotPolynomial { max_degree });
+        }
+        leading.push(row[0].clone());
+        row = row.windows(2).map(|pair| &pair[1] - &pair[0]).collect();
+    }
+}
+
+// Degree of the polynomial through values at x = 0, 1, 2, ..., the zero sequence counts as 0
+pub fn degree<T: Clone + Into<BigInt>>(values: &[T], max_degree: usize) -> Result<usize, PolyError> {
+    leading_differences(&to_big(values), max_degree).map(|leading| leading.len().saturating_sub(1))
+}
+
+// Evaluates the polynomial through values at x = 0, 1, 2, ... anywhere, using the Newton form
+// sum of delta^k * C(x, k). The generalized binomial stays an integer for negative x too.
+pub fn newton_value_at<T: Clone + Into<BigInt>>(values: &[T], x: i64, max_degree: usize) -> Result<BigInt, PolyError> {
+    let leading = leading_differences(&to_big(values), max_degree)?;
+    let x = BigInt::from(x);
+    let mut result = BigInt::zero();
+    let mut binomial = BigInt::one();
+    for (k, difference) in leading.iter().enumerate() {
+        result += difference * &binomial;
+        binomial = binomial * (&x - k) / (k + 1);
+    }
+    Ok(result)
+}
+
+// The value steps places after the last one
+pub fn extrapolate_forward<T: Clone + Into<BigInt>>(values: &[T], steps: usize, max_degree: usize) -> Result<BigInt, PolyError> {
+    let x = (values.len() + steps) as i64 - 1;
+    newton_value_at(values, x, max_degree)
+}
+
+// The value steps places before the first one
+pub fn extrapolate_backward<T: Clone + Into<BigInt>>(values: &[T], steps: usize, max_degree: usize) -> Result<BigInt, PolyError> {
+    newton_value_at(values, -(steps as i64), max_degree)
+}
+
+// Lagrange interpolation through arbitrarily spaced points, summed over one common denominator
+// so the result is exact. Fails when the polynomial does not take an integer value at x.
+pub fn lagrange_value_at<T: Clone + Into<BigInt>>(points: &[(i64, T)], x: i64) -> Result<BigInt, PolyError> {
+    if points.is_empty() {
+        return Err(PolyError::Empty);
+    }
+    let mut numerator = BigInt::zero();
+    let mut denominator = BigInt::one();
+    for (i, (xi, yi)) in points.iter().enumerate() {
+        let mut term_numerator: BigInt = yi.clone().into();
+        let mut term_denominator = BigInt::one();
+        for (j, (xj, _)) in points.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if xi == xj {
+                return Err(PolyError::DuplicatePoint(*xi));
+            }
+            term_numerator *= x - xj;
+            term_denominator *= xi - xj;
+        }
+        numerator = numerator * &term_denominator + term_numerator * &denominator;
+        denominator *= term_denominator;
+    }
+    if !(&numerator % &denominator).is_zero() {
+        return Err(PolyError::NotAnInteger);
+    }
+    Ok(numerator / denominator)
+}
+
+// a * x^2 + b * x + c with exact coefficients
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Quadratic {
+    pub a: Rational,
+    pub b: Rational,
+    pub c: Rational,
+}
+
+impl Quadratic {
+    // None when two of the points share an x
+    pub fn fit(points: [(i128, i128); 3]) -> Option<Quadratic> {
+        let coefficients: Vec<Vec<i128>> = points.iter().map(|(x, _)| vec![x * x, *x, 1]).collect();
+        let constants: Vec<i128> = points.iter().map(|(_, y)| *y).collect();
+        match linear::solve_integers(&coefficients, &constants) {
+            LinearSolution::Unique(solution) => Some(Quadratic { a: solution[0], b: solution[1], c: solution[2] }),
+            _ => None,
+        }
+    }
+
+    pub fn value_at(&self, x: i128) -> Rational {
+        let x = Rational::integer(x);
+        (self.a * x + self.b) * x + self.c
+    }
+}
+
+#[cfg(test)]
+mod poly_tests {
+    use super::*;
+
+    #[test]
+    fn extrapolate_in_both_directions() {
+        assert_eq!(extrapolate_forward(&[0, 3, 6, 9, 12, 15], 1, 5), Ok(BigInt::from(18)));
+        assert_eq!(extrapolate_forward(&[10, 13, 16, 21, 30, 45], 1, 5), Ok(BigInt::from(68)));
+        assert_eq!(extrapolate_backward(&[10, 13, 16, 21, 30, 45], 1, 5), Ok(BigInt::from(5)));
+        assert_eq!(extrapolate_backward(&[1, 3, 6, 10, 15, 21], 3, 5), Ok(BigInt::from(1)));
+    }
+
+    #[test]
+    fn newton_value_far_away_does_not_overflow() {
+        // Squares, evaluated where the result no longer fits in i64
+        let squares: Vec<i64> = (0..5).map(|x| x * x).collect();
+        let x = 4_000_000_000i64;
+        assert_eq!(newton_value_at(&squares, x, 4), Ok(BigInt::from(x) * x));
+        assert_eq!(newton_value_at(&squares, -7, 4), Ok(BigInt::from(49)));
+    }
+
+    #[test]
+    fn degree_of_sequences() {
+        assert_eq!(degree(&[0, 0, 0], 2), Ok(0));
+        assert_eq!(degree(&[4, 4, 4], 2), Ok(0));
+        assert_eq!(degree(&[1, 4, 9, 16], 2), Ok(2));
+        assert_eq!(degree::<i64>(&[], 2), Err(PolyError::Empty));
+    }
+
+    #[test]
+    fn not_polynomial_within_bound_is_reported() {
+        let powers: Vec<i64> = (0..10).map(|x| 1 << x).collect();
+        assert_eq!(extrapolate_forward(&powers, 1, 5), Err(PolyError::NotPolynomial { max_degree: 5 }));
+        assert_eq!(degree(&[1, 4, 9, 16], 1), Err(PolyError::NotPolynomial { max_degree: 1 }));
+        // Three values always fit a quadratic, so only a tighter bound can reject them
+        assert_eq!(degree(&[1, 4, 9], 5), Ok(2));
+    }
+
+    #[test]
+    fn lagrange_through_uneven_points() {
+        // y = x^2 - 2x + 3
+        let points = [(-2, 11), (1, 2), (5, 18)];
+        assert_eq!(lagrange_value_at(&points, 10), Ok(BigInt::from(83)));
+        assert_eq!(lagrange_value_at(&[(0, 0), (2, 1)], 1), Err(PolyError::NotAnInteger));
+        assert_eq!(lagrange_value_at(&[(3, 1), (3, 2)], 0), Err(PolyError::DuplicatePoint(3)));
+    }
+
+    #[test]
+    fn quadratic_fit_for_periodic_growth() {
+        // Samples taken every 131 steps starting at 65, extrapolated by cycle count
+        let fit = Quadratic::fit([(0, 3911), (1, 34786), (2, 96435)]).unwrap();
+        assert_eq!(fit.a, Rational::integer(15387));
+        assert_eq!(fit.value_at(3), Rational::integer(188858));
+        assert_eq!(
+            fit.value_at(202300).to_integer().map(BigInt::from),
+            newton_value_at(&[3911, 34786, 96435], 202300, 2).ok()
+        );
+        assert_eq!(Quadratic::fit([(1, 1), (1, 2), (3, 3)]), None);
+        assert_eq!(Quadratic::fit([(0, 0), (2, 1), (4, 0)]).unwrap().a, Rational::new(-1, 4));
+    }
+}
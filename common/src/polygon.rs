@@ -0,0 +1,174 @@
+use crate::geom::{Direction, Point};
+use crate::math::gcd;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Containment {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+// A simple polygon over integer vertices, closed implicitly from the last vertex back to the
+// first. Vertices may be listed in either winding order and collinear vertices are allowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Polygon {
+        Polygon { vertices }
+    }
+
+    // Builds the outline traced by walking each (direction, distance) step from start, as in a
+    // dig plan. The walk is expected to return to start.
+    pub fn from_steps(start: Point, steps: &[(Direction, i64)]) -> Polygon {
+        let mut vertices = Vec::with_capacity(steps.len());
+        let mut current = start;
+        for (direction, distance) in steps {
+            vertices.push(current);
+            current += direction.to_vector() * *distance;
+        }
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        self.vertices.iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    }
+
+    // Shoelace formula, positive when the vertices run clockwise on screen (y grows south)
+    pub fn doubled_signed_area(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| a.x as i128 * b.y as i128 - b.x as i128 * a.y as i128)
+            .sum()
+    }
+
+    // Twice the area so that half-integer areas stay exact
+    pub fn doubled_area(&self) -> i128 {
+        self.doubled_signed_area().abs()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.doubled_area() as f64 / 2.0
+    }
+
+    // Lattice points lying on the outline, including the vertices
+    pub fn boundary_points(&self) -> i128 {
+        self.edges()
+            .map(|(a, b)| gcd(b.x as i128 - a.x as i128, b.y as i128 - a.y as i128))
+            .sum()
+    }
+
+    // Pick's theorem: A = I + B / 2 - 1, rearranged to keep everything integral
+    pub fn interior_points(&self) -> i128 {
+        (self.doubled_area() - self.boundary_points() + 2) / 2
+    }
+
+    // Interior plus boundary, i.e. the number of grid cells covered when each vertex is a cell
+    pub fn enclosed_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn contains(&self, point: Point) -> Containment {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let cross = (b - a).x as i128 * (point - a).y as i128 - (b - a).y as i128 * (point - a).x as i128;
+            let within_x = a.x.min(b.x) <= point.x && point.x <= a.x.max(b.x);
+            let within_y = a.y.min(b.y) <= point.y && point.y <= a.y.max(b.y);
+            if cross == 0 && within_x && within_y {
+                return Containment::Boundary;
+            }
+            // Cast a ray towards +x, counting edges that straddle the point's row half-open so a
+            // vertex on the ray is only counted once
+            if (a.y > point.y) != (b.y > point.y) {
+                let crosses_right = if b.y > a.y { cross > 0 } else { cross < 0 };
+                if crosses_right {
+                    inside = !inside;
+                }
+            }
+        }
+        if inside { Containment::Inside } else { Containment::Outside }
+    }
+}
+
+#[cfg(test)]
+mod polygon_tests {
+    use super::*;
+
+    fn square(size: i64) -> Polygon {
+        Polygon::new(vec![Point::new(0, 0), Point::new(size, 0), Point::new(size, size), Point::new(0, size)])
+    }
+
+    #[test]
+    fn square_area_and_lattice_counts() {
+        let polygon = square(4);
+        assert_eq!(polygon.doubled_area(), 32);
+        assert_eq!(polygon.area(), 16.0);
+        assert_eq!(polygon.boundary_points(), 16);
+        assert_eq!(polygon.interior_points(), 9);
+        assert_eq!(polygon.enclosed_points(), 25);
+    }
+
+    #[test]
+    fn winding_order_only_changes_sign() {
+        let clockwise = square(3);
+        let mut reversed = clockwise.vertices().to_vec();
+        reversed.reverse();
+        let counter_clockwise = Polygon::new(reversed);
+        assert_eq!(clockwise.doubled_signed_area(), 18);
+        assert_eq!(counter_clockwise.doubled_signed_area(), -18);
+        assert_eq!(counter_clockwise.interior_points(), 4);
+    }
+
+    #[test]
+    fn triangle_with_half_integer_area() {
+        let polygon = Polygon::new(vec![Point::new(0, 0), Point::new(3, 0), Point::new(0, 1)]);
+        assert_eq!(polygon.doubled_area(), 3);
+        assert_eq!(polygon.area(), 1.5);
+        assert_eq!(polygon.boundary_points(), 5);
+        assert_eq!(polygon.interior_points(), 0);
+    }
+
+    #[test]
+    fn from_steps_matches_dig_plan_example() {
+        let steps = [
+            (Direction::East, 6), (Direction::South, 5), (Direction::West, 2), (Direction::South, 2),
+            (Direction::East, 2), (Direction::South, 2), (Direction::West, 5), (Direction::North, 2),
+            (Direction::West, 1), (Direction::North, 2), (Direction::East, 2), (Direction::North, 3),
+            (Direction::West, 2), (Direction::North, 2),
+        ];
+        let polygon = Polygon::from_steps(Point::ORIGIN, &steps);
+        assert_eq!(polygon.boundary_points(), 38);
+        assert_eq!(polygon.enclosed_points(), 62);
+    }
+
+    #[test]
+    fn large_coordinates_do_not_overflow() {
+        let polygon = square(3_000_000_000);
+        assert_eq!(polygon.doubled_area(), 2 * 9_000_000_000_000_000_000);
+    }
+
+    #[test]
+    fn contains_classifies_points() {
+        // An L shape, the notch at (3, 0)..(4, 2) is outside
+        let polygon = Polygon::new(vec![
+            Point::new(0, 0), Point::new(2, 0), Point::new(2, 2),
+            Point::new(4, 2), Point::new(4, 4), Point::new(0, 4),
+        ]);
+        assert_eq!(polygon.contains(Point::new(1, 1)), Containment::Inside);
+        assert_eq!(polygon.contains(Point::new(3, 3)), Containment::Inside);
+        assert_eq!(polygon.contains(Point::new(3, 1)), Containment::Outside);
+        assert_eq!(polygon.contains(Point::new(5, 2)), Containment::Outside);
+        assert_eq!(polygon.contains(Point::new(2, 1)), Containment::Boundary);
+        assert_eq!(polygon.contains(Point::new(4, 4)), Containment::Boundary);
+        // Rays through vertices must not be double counted
+        assert_eq!(polygon.contains(Point::new(1, 2)), Containment::Inside);
+        assert_eq!(polygon.contains(Point::new(-1, 2)), Containment::Outside);
+    }
+}
@@ -1,3 +1,24 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeError {
+    Parse(String),
+    Overflow,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RangeError::Parse(input) => write!(f, "Invalid range: {:?}", input),
+            RangeError::Overflow => write!(f, "Range end does not fit in an i64"),
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+// Both start and end are inclusive, a range with end < start is empty
 #[derive(Debug, Clone, PartialEq)]
 pub struct Range {
     pub start: i64,
@@ -5,7 +26,39 @@ pub struct Range {
 }
 
 impl Range {
+    pub fn new(start: i64, end: i64) -> Range {
+        Range { start, end }
+    }
+
+    // Fails when the inclusive end does not fit, such as an empty range starting at i64::MIN
+    pub fn half_open(start: i64, end_exclusive: i64) -> Result<Range, RangeError> {
+        let end = end_exclusive.checked_sub(1).ok_or(RangeError::Overflow)?;
+        Ok(Range { start, end })
+    }
+
+    pub fn with_length(start: i64, length: i64) -> Result<Range, RangeError> {
+        let end = length.checked_sub(1)
+            .and_then(|last_offset| start.checked_add(last_offset))
+            .ok_or(RangeError::Overflow)?;
+        Ok(Range { start, end })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end < self.start
+    }
+
+    pub fn len(&self) -> i64 {
+        if self.is_empty() { 0 } else { self.end - self.start + 1 }
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value <= self.end
+    }
+
     pub fn find_overlap(&self, other: &Range) -> Option<Range> {
+        if self.is_empty() || other.is_empty() {
+            return None;
+        }
         if self.end < other.start || self.start > other.end {
             return None;
         }
@@ -46,10 +99,91 @@ impl Range {
     }
 }
 
+impl From<std::ops::RangeInclusive<i64>> for Range {
+    fn from(range: std::ops::RangeInclusive<i64>) -> Range {
+        Range::new(*range.start(), *range.end())
+    }
+}
+
+impl TryFrom<std::ops::Range<i64>> for Range {
+    type Error = RangeError;
+
+    fn try_from(range: std::ops::Range<i64>) -> Result<Range, RangeError> {
+        Range::half_open(range.start, range.end)
+    }
+}
+
+impl From<Range> for std::ops::RangeInclusive<i64> {
+    fn from(range: Range) -> std::ops::RangeInclusive<i64> {
+        range.start..=range.end
+    }
+}
+
+impl TryFrom<Range> for std::ops::Range<i64> {
+    type Error = RangeError;
+
+    fn try_from(range: Range) -> Result<std::ops::Range<i64>, RangeError> {
+        let end = range.end.checked_add(1).ok_or(RangeError::Overflow)?;
+        Ok(range.start..end)
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl FromStr for Range {
+    type Err = RangeError;
+
+    // Accepts "start-end" or a single value, either of which may be negative (e.g. "-5--2")
+    fn from_str(s: &str) -> Result<Range, RangeError> {
+        let trimmed = s.trim();
+        let error = || RangeError::Parse(s.to_string());
+        let separator = trimmed.char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '-')
+            .map(|(i, _)| i);
+        match separator {
+            Some(i) => {
+                let start = trimmed[..i].trim().parse::<i64>().map_err(|_| error())?;
+                let end = trimmed[i + 1..].trim().parse::<i64>().map_err(|_| error())?;
+                Ok(Range::new(start, end))
+            }
+            None => {
+                let value = trimmed.parse::<i64>().map_err(|_| error())?;
+                Ok(Range::new(value, value))
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RangeSet {
     pub ranges: Vec<Range>,
 }
+
+impl fmt::Display for RangeSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = self.ranges.iter().map(|r| r.to_string()).collect();
+        write!(f, "{}", parts.join(","))
+    }
+}
+
+impl FromStr for RangeSet {
+    type Err = RangeError;
+
+    fn from_str(s: &str) -> Result<RangeSet, RangeError> {
+        if s.trim().is_empty() {
+            return Ok(RangeSet { ranges: Vec::new() });
+        }
+        Ok(RangeSet {
+            ranges: s.split(',').map(|part| part.parse::<Range>()).collect::<Result<Vec<Range>, RangeError>>()?
+        })
+    }
+}
+
 impl RangeSet {
     pub fn simplify(&self) -> RangeSet {
         let mut simplified_ranges: Vec<Range> = Vec::new();
@@ -90,15 +224,167 @@ impl RangeSet {
         result.simplify()
     }
 
+    pub fn coverage(&self) -> Coverage {
+        Coverage::from_ranges(&self.ranges)
+    }
+
     pub fn subtract_ranges(&self, other: &RangeSet) -> RangeSet {
         let mut adjusted_range_set = self.clone();
         for other_range in &other.ranges {
-            adjusted_range_set = adjusted_range_set.subtract_range(&other_range);
+            adjusted_range_set = adjusted_range_set.subtract_range(other_range);
         }
         adjusted_range_set
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageSegment {
+    pub range: Range,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Coverage {
+    // Maximal segments with a constant, non-zero number of covering ranges, in order
+    pub segments: Vec<CoverageSegment>,
+}
+
+impl Coverage {
+    pub fn from_ranges(ranges: &[Range]) -> Coverage {
+        let mut events: Vec<(i64, i64)> = Vec::new();
+        for range in ranges {
+            if range.is_empty() {
+                continue;
+            }
+            events.push((range.start, 1));
+            // A range ending at i64::MAX never closes, it stays counted to the end
+            if let Some(after_end) = range.end.checked_add(1) {
+                events.push((after_end, -1));
+            }
+        }
+        events.sort();
+
+        let mut segments: Vec<CoverageSegment> = Vec::new();
+        let mut count: i64 = 0;
+        let mut index = 0;
+        while index < events.len() {
+            let position = events[index].0;
+            while index < events.len() && events[index].0 == position {
+                count += events[index].1;
+                index += 1;
+            }
+            if count == 0 {
+                continue;
+            }
+            let range = Range {
+                start: position,
+                end: events.get(index).map_or(i64::MAX, |event| event.0 - 1),
+            };
+            match segments.last_mut() {
+                Some(last) if last.count == count as usize && last.range.end.checked_add(1) == Some(range.start) => {
+                    last.range.end = range.end;
+                }
+                _ => segments.push(CoverageSegment { range, count: count as usize }),
+            }
+        }
+        Coverage {
+            segments
+        }
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.segments.iter().map(|s| s.count).max().unwrap_or(0)
+    }
+
+    pub fn count_at(&self, point: i64) -> usize {
+        let index = self.segments.partition_point(|s| s.range.end < point);
+        match self.segments.get(index) {
+            Some(segment) if segment.range.start <= point => segment.count,
+            _ => 0,
+        }
+    }
+
+    pub fn covered_at_least(&self, k: usize) -> RangeSet {
+        RangeSet {
+            ranges: self.segments.iter()
+                .filter(|s| s.count >= k)
+                .map(|s| s.range.clone())
+                .collect()
+        }.simplify()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IntervalTree<T> {
+    // Entries sorted by range start, treated as an implicit balanced tree where the root of
+    // any slice is its midpoint. max_ends[i] holds the largest end within the subtree at i.
+    entries: Vec<(Range, T)>,
+    max_ends: Vec<i64>,
+}
+
+impl<T> IntervalTree<T> {
+    pub fn new(mut entries: Vec<(Range, T)>) -> IntervalTree<T> {
+        entries.sort_by_key(|(range, _)| (range.start, range.end));
+        let mut max_ends = vec![i64::MIN; entries.len()];
+        Self::build_max_ends(&entries, &mut max_ends, 0, entries.len());
+        IntervalTree {
+            entries,
+            max_ends
+        }
+    }
+
+    fn build_max_ends(entries: &[(Range, T)], max_ends: &mut [i64], low: usize, high: usize) -> i64 {
+        if low >= high {
+            return i64::MIN;
+        }
+        let mid = low + (high - low) / 2;
+        let left = Self::build_max_ends(entries, max_ends, low, mid);
+        let right = Self::build_max_ends(entries, max_ends, mid + 1, high);
+        max_ends[mid] = entries[mid].0.end.max(left).max(right);
+        max_ends[mid]
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn find_containing(&self, point: i64) -> Vec<(&Range, &T)> {
+        self.find_overlapping(&Range { start: point, end: point })
+    }
+
+    pub fn find_overlapping(&self, range: &Range) -> Vec<(&Range, &T)> {
+        let mut results = Vec::new();
+        self.collect_overlapping(range, 0, self.entries.len(), &mut results);
+        results
+    }
+
+    fn collect_overlapping<'a>(&'a self, range: &Range, low: usize, high: usize,
+                               results: &mut Vec<(&'a Range, &'a T)>) {
+        if low >= high {
+            return;
+        }
+        let mid = low + (high - low) / 2;
+        // Nothing in this subtree reaches far enough right to touch the range
+        if self.max_ends[mid] < range.start {
+            return;
+        }
+        self.collect_overlapping(range, low, mid, results);
+        let (entry_range, value) = &self.entries[mid];
+        if entry_range.start > range.end {
+            // Everything to the right starts even later
+            return;
+        }
+        if entry_range.end >= range.start {
+            results.push((entry_range, value));
+        }
+        self.collect_overlapping(range, mid + 1, high, results);
+    }
+}
+
 #[cfg(test)]
 mod range_tests {
     use super::*;
@@ -194,6 +480,72 @@ mod range_tests {
     }
 }
 
+#[cfg(test)]
+mod range_conversion_tests {
+    use super::*;
+
+    #[test]
+    fn constructors_produce_inclusive_ranges() {
+        assert_eq!(Range::new(1, 5), Range { start: 1, end: 5 });
+        assert_eq!(Range::half_open(1, 5), Ok(Range { start: 1, end: 4 }));
+        assert_eq!(Range::with_length(1, 5), Ok(Range { start: 1, end: 5 }));
+    }
+
+    #[test]
+    fn constructors_when_end_does_not_fit_return_overflow() {
+        assert_eq!(Range::half_open(i64::MIN, i64::MIN), Err(RangeError::Overflow));
+        assert_eq!(Range::with_length(i64::MIN, 0), Err(RangeError::Overflow));
+        assert_eq!(Range::with_length(i64::MIN, i64::MIN), Err(RangeError::Overflow));
+        assert_eq!(Range::with_length(i64::MAX, 2), Err(RangeError::Overflow));
+        assert_eq!(Range::with_length(-1, i64::MAX), Ok(Range::new(-1, i64::MAX - 2)));
+        assert_eq!(Range::half_open(0, i64::MIN + 1), Ok(Range::new(0, i64::MIN)));
+    }
+
+    #[test]
+    fn is_empty_when_end_before_start() {
+        assert!(!Range::new(3, 3).is_empty());
+        assert!(Range::half_open(3, 3).unwrap().is_empty());
+        assert_eq!(Range::half_open(3, 3).unwrap().len(), 0);
+        assert_eq!(Range::new(3, 7).len(), 5);
+    }
+
+    #[test]
+    fn find_overlap_when_empty_returns_none() {
+        let range1 = Range::new(5, 4);
+        let range2 = Range::new(1, 10);
+        assert_eq!(range1.find_overlap(&range2), None);
+        assert_eq!(range2.find_overlap(&range1), None);
+    }
+
+    #[test]
+    fn from_std_ranges_converts_ends() {
+        assert_eq!(Range::from(1..=5), Range::new(1, 5));
+        assert_eq!(Range::try_from(1..5), Ok(Range::new(1, 4)));
+        assert_eq!(Range::try_from(i64::MIN..i64::MIN), Err(RangeError::Overflow));
+        assert_eq!(std::ops::RangeInclusive::from(Range::new(1, 5)), 1..=5);
+        assert_eq!(std::ops::Range::try_from(Range::new(1, 5)), Ok(1..6));
+        assert_eq!(std::ops::Range::try_from(Range::new(1, i64::MAX)), Err(RangeError::Overflow));
+    }
+
+    #[test]
+    fn parse_range_handles_single_values_and_negatives() {
+        assert_eq!("1-5".parse::<Range>(), Ok(Range::new(1, 5)));
+        assert_eq!("7".parse::<Range>(), Ok(Range::new(7, 7)));
+        assert_eq!("-5--2".parse::<Range>(), Ok(Range::new(-5, -2)));
+        assert_eq!("-5-2".parse::<Range>(), Ok(Range::new(-5, 2)));
+        assert_eq!("a-2".parse::<Range>(), Err(RangeError::Parse("a-2".to_string())));
+    }
+
+    #[test]
+    fn range_set_display_round_trips() {
+        let range_set = "1-5,8-9,-3--1".parse::<RangeSet>().unwrap();
+        assert_eq!(range_set.ranges, vec![Range::new(1, 5), Range::new(8, 9), Range::new(-3, -1)]);
+        assert_eq!(range_set.to_string(), "1-5,8-9,-3--1");
+        assert_eq!("".parse::<RangeSet>().unwrap().ranges.len(), 0);
+        assert!("1-5,,8".parse::<RangeSet>().is_err());
+    }
+}
+
 #[cfg(test)]
 mod range_set_tests {
     use super::*;
@@ -356,4 +708,157 @@ mod range_set_tests {
         assert_eq!(6, result.ranges[0].start);
         assert_eq!(7, result.ranges[0].end);
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod coverage_tests {
+    use super::*;
+
+    fn build_coverage() -> Coverage {
+        RangeSet {
+            ranges: vec![
+                Range { start: 1, end: 5 },
+                Range { start: 3, end: 8 },
+                Range { start: 4, end: 4 },
+                Range { start: 12, end: 14 },
+            ]
+        }.coverage()
+    }
+
+    #[test]
+    fn from_ranges_splits_into_constant_segments() {
+        let coverage = build_coverage();
+        assert_eq!(coverage.segments, vec![
+            CoverageSegment { range: Range { start: 1, end: 2 }, count: 1 },
+            CoverageSegment { range: Range { start: 3, end: 3 }, count: 2 },
+            CoverageSegment { range: Range { start: 4, end: 4 }, count: 3 },
+            CoverageSegment { range: Range { start: 5, end: 5 }, count: 2 },
+            CoverageSegment { range: Range { start: 6, end: 8 }, count: 1 },
+            CoverageSegment { range: Range { start: 12, end: 14 }, count: 1 },
+        ]);
+    }
+
+    #[test]
+    fn from_ranges_when_adjacent_merges_equal_counts() {
+        let coverage = Coverage::from_ranges(&[
+            Range { start: 1, end: 2 },
+            Range { start: 3, end: 4 },
+        ]);
+        assert_eq!(coverage.segments, vec![
+            CoverageSegment { range: Range { start: 1, end: 4 }, count: 1 },
+        ]);
+    }
+
+    #[test]
+    fn from_ranges_when_ending_at_max_keeps_last_segment() {
+        let coverage = Coverage::from_ranges(&[
+            Range { start: i64::MAX - 5, end: i64::MAX },
+            Range { start: i64::MAX - 2, end: i64::MAX },
+            Range { start: i64::MIN, end: i64::MIN + 1 },
+        ]);
+        assert_eq!(coverage.segments, vec![
+            CoverageSegment { range: Range { start: i64::MIN, end: i64::MIN + 1 }, count: 1 },
+            CoverageSegment { range: Range { start: i64::MAX - 5, end: i64::MAX - 3 }, count: 1 },
+            CoverageSegment { range: Range { start: i64::MAX - 2, end: i64::MAX }, count: 2 },
+        ]);
+        assert_eq!(coverage.count_at(i64::MAX), 2);
+    }
+
+    #[test]
+    fn from_ranges_when_empty_returns_no_segments() {
+        let coverage = Coverage::from_ranges(&[]);
+        assert_eq!(coverage.segments.len(), 0);
+        assert_eq!(coverage.max_depth(), 0);
+    }
+
+    #[test]
+    fn max_depth_returns_highest_count() {
+        assert_eq!(build_coverage().max_depth(), 3);
+    }
+
+    #[test]
+    fn count_at_returns_covering_count() {
+        let coverage = build_coverage();
+        assert_eq!(coverage.count_at(0), 0);
+        assert_eq!(coverage.count_at(2), 1);
+        assert_eq!(coverage.count_at(4), 3);
+        assert_eq!(coverage.count_at(10), 0);
+        assert_eq!(coverage.count_at(14), 1);
+    }
+
+    #[test]
+    fn covered_at_least_returns_merged_ranges() {
+        let coverage = build_coverage();
+        let result = coverage.covered_at_least(2);
+        assert_eq!(result.ranges, vec![Range { start: 3, end: 5 }]);
+        let result = coverage.covered_at_least(1);
+        assert_eq!(result.ranges, vec![Range { start: 1, end: 8 }, Range { start: 12, end: 14 }]);
+        assert_eq!(coverage.covered_at_least(4).ranges.len(), 0);
+    }
+}
+
+#[cfg(test)]
+mod interval_tree_tests {
+    use super::*;
+
+    fn build_tree() -> IntervalTree<&'static str> {
+        IntervalTree::new(vec![
+            (Range { start: 10, end: 20 }, "a"),
+            (Range { start: 1, end: 5 }, "b"),
+            (Range { start: 4, end: 12 }, "c"),
+            (Range { start: 30, end: 40 }, "d"),
+            (Range { start: 15, end: 15 }, "e"),
+        ])
+    }
+
+    fn labels(results: Vec<(&Range, &&'static str)>) -> Vec<&'static str> {
+        let mut labels: Vec<&'static str> = results.iter().map(|(_, label)| **label).collect();
+        labels.sort();
+        labels
+    }
+
+    #[test]
+    fn find_containing_when_none_returns_empty() {
+        let tree = build_tree();
+        assert_eq!(tree.find_containing(25).len(), 0);
+        assert_eq!(tree.find_containing(0).len(), 0);
+        assert_eq!(tree.find_containing(41).len(), 0);
+    }
+
+    #[test]
+    fn find_containing_when_multiple_returns_all() {
+        let tree = build_tree();
+        assert_eq!(labels(tree.find_containing(4)), vec!["b", "c"]);
+        assert_eq!(labels(tree.find_containing(15)), vec!["a", "e"]);
+        assert_eq!(labels(tree.find_containing(40)), vec!["d"]);
+    }
+
+    #[test]
+    fn find_overlapping_returns_all_overlaps() {
+        let tree = build_tree();
+        assert_eq!(labels(tree.find_overlapping(&Range { start: 12, end: 30 })), vec!["a", "c", "d", "e"]);
+        assert_eq!(labels(tree.find_overlapping(&Range { start: 21, end: 29 })), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn find_overlapping_matches_linear_scan() {
+        let entries: Vec<(Range, usize)> = (0..200)
+            .map(|i| {
+                let start = (i * 37) % 500;
+                (Range { start, end: start + (i * 13) % 40 }, i as usize)
+            })
+            .collect();
+        let tree = IntervalTree::new(entries.clone());
+        for start in (0..560).step_by(7) {
+            let query = Range { start, end: start + 5 };
+            let mut expected: Vec<usize> = entries.iter()
+                .filter(|(range, _)| range.find_overlap(&query).is_some())
+                .map(|(_, i)| *i)
+                .collect();
+            expected.sort();
+            let mut actual: Vec<usize> = tree.find_overlapping(&query).iter().map(|(_, i)| **i).collect();
+            actual.sort();
+            assert_eq!(expected, actual);
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+// Only final distances are kept. BFS distances are final once a state is discovered, while
+// dijkstra and astar drop the states they had not settled yet when they stop early at a goal.
+#[derive(Debug, Clone)]
+pub struct SearchResult<S, C> {
+    pub distances: HashMap<S, C>,
+    predecessors: HashMap<S, S>,
+    // The first goal state reached, the search stops as soon as it is settled
+    pub goal: Option<S>,
+}
+
+impl<S: Hash + Eq + Clone, C: Copy> SearchResult<S, C> {
+    pub fn distance(&self, state: &S) -> Option<C> {
+        self.distances.get(state).copied()
+    }
+
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.distances.contains_key(state) {
+            return None;
+        }
+        let mut path = vec![state.clone()];
+        while let Some(previous) = self.predecessors.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    pub fn goal_distance(&self) -> Option<C> {
+        self.distance(self.goal.as_ref()?)
+    }
+
+    pub fn goal_path(&self) -> Option<Vec<S>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+}
+
+pub fn bfs<S, I, F, G>(start: S, mut successors: F, mut is_goal: G) -> SearchResult<S, usize>
+    where S: Hash + Eq + Clone, I: IntoIterator<Item = S>, F: FnMut(&S) -> I, G: FnMut(&S) -> bool {
+    let mut distances = HashMap::new();
+    let mut predecessors = HashMap::new();
+    let mut queue = VecDeque::new();
+    distances.insert(start.clone(), 0);
+    queue.push_back(start);
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            return SearchResult { distances, predecessors, goal: Some(state) };
+        }
+        let distance = distances[&state];
+        for next in successors(&state) {
+            if distances.contains_key(&next) {
+                continue;
+            }
+            distances.insert(next.clone(), distance + 1);
+            predecessors.insert(next.clone(), state.clone());
+            queue.push_back(next);
+        }
+    }
+    SearchResult { distances, predecessors, goal: None }
+}
+
+pub fn dijkstra<S, C, I, F, G>(start: S, successors: F, is_goal: G) -> SearchResult<S, C>
+    where S: Hash + Eq + Clone, C: Ord + Copy + Default + Add<Output = C>,
+          I: IntoIterator<Item = (S, C)>, F: FnMut(&S) -> I, G: FnMut(&S) -> bool {
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+// The heuristic must never overestimate the remaining cost for the goal distance to be optimal
+pub fn astar<S, C, I, F, H, G>(start: S, mut successors: F, mut heuristic: H, mut is_goal: G)
+    -> SearchResult<S, C>
+    where S: Hash + Eq + Clone, C: Ord + Copy + Default + Add<Output = C>,
+          I: IntoIterator<Item = (S, C)>, F: FnMut(&S) -> I, H: FnMut(&S) -> C,
+          G: FnMut(&S) -> bool {
+    let mut distances: HashMap<S, C> = HashMap::new();
+    let mut predecessors: HashMap<S, S> = HashMap::new();
+    // Heap entries index into states so S itself does not need to be Ord
+    let mut states: Vec<(S, C)> = Vec::new();
+    let mut heap = BinaryHeap::new();
+    let mut settled: HashSet<S> = HashSet::new();
+
+    distances.insert(start.clone(), C::default());
+    heap.push(Reverse((heuristic(&start), states.len())));
+    states.push((start, C::default()));
+
+    while let Some(Reverse((_, index))) = heap.pop() {
+        let (state, distance) = states[index].clone();
+        if distances[&state] < distance {
+            continue;
+        }
+        settled.insert(state.clone());
+        if is_goal(&state) {
+            // States still waiting in the heap only have tentative distances
+            distances.retain(|s, _| settled.contains(s));
+            predecessors.retain(|s, _| settled.contains(s));
+            return SearchResult { distances, predecessors, goal: Some(state) };
+        }
+        for (next, cost) in successors(&state) {
+            let next_distance = distance + cost;
+            if distances.get(&next).is_some_and(|d| *d <= next_distance) {
+                continue;
+            }
+            distances.insert(next.clone(), next_distance);
+            predecessors.insert(next.clone(), state.clone());
+            heap.push(Reverse((next_distance + heuristic(&next), states.len())));
+            states.push((next, next_distance));
+        }
+    }
+    SearchResult { distances, predecessors, goal: None }
+}
+
+#[cfg(test)]
+mod search_tests {
+    use super::*;
+    use crate::geom::{Direction, Point};
+    use crate::grid::Grid;
+
+    fn build_maze() -> Grid<char> {
+        Grid::parse(&[
+            "S..#....",
+            ".#.#.##.",
+            ".#...#..",
+            ".####.#.",
+            "......#E",
+        ], Some).unwrap()
+    }
+
+    fn open_neighbours(grid: &Grid<char>, point: &Point) -> Vec<Point> {
+        point.neighbours4().filter(|p| grid.get_at(*p).is_some_and(|c| *c != '#')).collect()
+    }
+
+    #[test]
+    fn bfs_finds_shortest_path() {
+        let grid = build_maze();
+        let start = Point::new(0, 0);
+        let end = Point::new(7, 4);
+        let result = bfs(start, |p| open_neighbours(&grid, p), |p| *p == end);
+        assert_eq!(result.goal, Some(end));
+        assert_eq!(result.goal_distance(), Some(15));
+        let path = result.goal_path().unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!(path[0], start);
+        assert!(path.windows(2).all(|w| w[0].manhattan_distance(&w[1]) == 1));
+    }
+
+    #[test]
+    fn bfs_without_goal_returns_all_distances() {
+        let grid = build_maze();
+        let result = bfs(Point::new(0, 0), |p| open_neighbours(&grid, p), |_| false);
+        assert_eq!(result.goal, None);
+        assert_eq!(result.distance(&Point::new(0, 4)), Some(4));
+        assert_eq!(result.distance(&Point::new(1, 1)), None);
+        assert_eq!(result.distances.len(), grid.iter().filter(|(_, c)| **c != '#').count());
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_longer_route() {
+        // a -> b -> c -> d costs 3, a -> d costs 10
+        let edges = |s: &char| -> Vec<(char, u64)> {
+            match s {
+                'a' => vec![('b', 1), ('d', 10)],
+                'b' => vec![('c', 1)],
+                'c' => vec![('d', 1)],
+                _ => vec![],
+            }
+        };
+        let result = dijkstra('a', edges, |s| *s == 'd');
+        assert_eq!(result.goal_distance(), Some(3));
+        assert_eq!(result.goal_path(), Some(vec!['a', 'b', 'c', 'd']));
+    }
+
+    #[test]
+    fn dijkstra_when_stopping_early_keeps_only_settled_distances() {
+        // 2 is discovered at a tentative cost of 5 but the goal is settled first
+        let edges = |state: &u32| match state {
+            0 => vec![(1, 1), (2, 5)],
+            1 => vec![(3, 1)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        };
+        let result = dijkstra(0u32, edges, |s| *s == 3);
+        assert_eq!(result.goal_distance(), Some(2u32));
+        assert_eq!(result.distance(&2), None);
+        assert_eq!(result.goal_path(), Some(vec![0, 1, 3]));
+        assert_eq!(dijkstra(0u32, edges, |_| false).distance(&2), Some(5));
+    }
+
+    #[test]
+    fn dijkstra_when_unreachable_returns_no_goal() {
+        let result = dijkstra(0u32, |s| if *s < 5 { vec![(s + 1, 2u64)] } else { vec![] }, |s| *s == 10);
+        assert_eq!(result.goal, None);
+        assert_eq!(result.distance(&5), Some(10));
+        assert_eq!(result.goal_path(), None);
+    }
+
+    #[test]
+    fn dijkstra_with_constrained_movement_state() {
+        // At most three steps may be taken in the same direction, forcing a detour
+        let grid = Grid::parse(&["11111", "11111"], |c| c.to_digit(10).map(|d| d as u64)).unwrap();
+        let start = (Point::new(0, 0), Direction::East, 0);
+        let end = Point::new(4, 0);
+        let successors = |(point, direction, run): &(Point, Direction, u32)| {
+            [*direction, direction.turn_left(), direction.turn_right()].into_iter()
+                .filter(|d| *d != *direction || *run < 3)
+                .filter_map(|d| {
+                    let next = point.step(d);
+                    let cost = *grid.get_at(next)?;
+                    let next_run = if d == *direction { run + 1 } else { 1 };
+                    Some(((next, d, next_run), cost))
+                })
+                .collect::<Vec<_>>()
+        };
+        let result = dijkstra(start, successors, |(p, _, _)| *p == end);
+        assert_eq!(result.goal_distance(), Some(6));
+        assert_eq!(result.goal_path().unwrap().len(), 7);
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let grid = build_maze();
+        let start = Point::new(0, 0);
+        let end = Point::new(7, 4);
+        let weighted = |p: &Point| open_neighbours(&grid, p).into_iter().map(|n| (n, 1u64)).collect::<Vec<_>>();
+        let expected = dijkstra(start, weighted, |p| *p == end);
+        let result = astar(start, weighted, |p| p.manhattan_distance(&end) as u64, |p| *p == end);
+        assert_eq!(result.goal_distance(), expected.goal_distance());
+        assert!(result.distances.len() <= expected.distances.len());
+    }
+}
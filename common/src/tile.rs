@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod tile_char_tests {
+    use crate::grid::Grid;
+    use crate::TileChar;
+
+    #[derive(Debug, Copy, Clone, PartialEq, TileChar)]
+    enum Tile {
+        #[tile('.', 'S')]
+        Open,
+        #[tile('#')]
+        Wall,
+        #[tile(render = 'O')]
+        Visited,
+    }
+
+    #[test]
+    fn try_from_char_accepts_aliases() {
+        assert_eq!(Tile::try_from('.'), Ok(Tile::Open));
+        assert_eq!(Tile::try_from('S'), Ok(Tile::Open));
+        assert_eq!(Tile::try_from('#'), Ok(Tile::Wall));
+    }
+
+    #[test]
+    fn try_from_char_when_unknown_or_render_only_returns_char() {
+        assert_eq!(Tile::try_from('x'), Err('x'));
+        assert_eq!(Tile::try_from('O'), Err('O'));
+    }
+
+    #[test]
+    fn tiles_render_through_grid_display() {
+        let mut grid = Grid::parse(&["S.#", "#.."], |c| Tile::try_from(c).ok()).unwrap();
+        grid.set(1, 1, Tile::Visited);
+        assert_eq!(grid.to_string(), "..#\n#O.\n");
+        assert_eq!(Tile::Wall.to_string(), "#");
+        assert_eq!(char::from(Tile::Visited), 'O');
+    }
+}
@@ -0,0 +1,195 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Error, Fields, Ident, LitStr, Result};
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field { name: String, separator: Option<String> },
+}
+
+// Splits "Game {id}: {sets:sep('; ')}" into literal text and placeholders, {{ and }} escape braces
+fn parse_format(format: &str) -> std::result::Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err(format!("Unclosed placeholder {{{}", placeholder)),
+                    }
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(parse_placeholder(&placeholder)?);
+            }
+            '}' => return Err("Unmatched } in format, use }} for a literal brace".to_string()),
+            _ => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+fn parse_placeholder(placeholder: &str) -> std::result::Result<Segment, String> {
+    let (name, modifier) = match placeholder.split_once(':') {
+        Some((name, modifier)) => (name.trim(), Some(modifier.trim())),
+        None => (placeholder.trim(), None),
+    };
+    if name.is_empty() {
+        return Err("Placeholders must name a field".to_string());
+    }
+    let separator = match modifier {
+        None => None,
+        Some(modifier) => {
+            let separator = modifier.strip_prefix("sep(")
+                .and_then(|m| m.strip_suffix(')'))
+                .and_then(|m| m.strip_prefix('\'').and_then(|m| m.strip_suffix('\'')))
+                .ok_or_else(|| format!("Unknown modifier {:?} on {}, expected sep('...')", modifier, name))?;
+            if separator.is_empty() {
+                return Err(format!("Separator for {} cannot be empty", name));
+            }
+            Some(separator.to_string())
+        }
+    };
+    Ok(Segment::Field { name: name.to_string(), separator })
+}
+
+fn find_format(input: &DeriveInput) -> Result<LitStr> {
+    let mut format = None;
+    for attribute in input.attrs.iter().filter(|a| a.path().is_ident("aoc")) {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                format = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("Unknown aoc attribute, expected format = \"...\""))
+            }
+        })?;
+    }
+    format.ok_or_else(|| Error::new_spanned(&input.ident, "AocParse needs #[aoc(format = \"...\")]"))
+}
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter()
+                .map(|f| f.ident.clone().unwrap())
+                .collect::<Vec<Ident>>(),
+            _ => return Err(Error::new_spanned(name, "AocParse only supports structs with named fields")),
+        },
+        _ => return Err(Error::new_spanned(name, "AocParse only supports structs")),
+    };
+
+    let format = find_format(input)?;
+    let segments = parse_format(&format.value()).map_err(|e| Error::new_spanned(&format, e))?;
+
+    let mut used: Vec<String> = Vec::new();
+    let mut steps = Vec::new();
+    for segment in &segments {
+        match segment {
+            Segment::Literal(literal) => steps.extend(literal_steps(literal)),
+            Segment::Field { name: field, separator } => {
+                let ident = fields.iter().find(|f| *f == field)
+                    .ok_or_else(|| Error::new_spanned(&format, format!("{} has no field named {}", name, field)))?;
+                if used.contains(field) {
+                    return Err(Error::new_spanned(&format, format!("Field {} appears more than once", field)));
+                }
+                used.push(field.clone());
+                let label = format!("{}.{}", name, field);
+                let parser = match separator {
+                    None => quote! { ::common::parse::Parse::parse },
+                    Some(separator) => quote! { |s| s.sep_by(#separator, ::common::parse::Parse::parse) },
+                };
+                steps.push(quote! { let #ident = scanner.field(#label, #parser)?; });
+            }
+        }
+    }
+    if let Some(missing) = fields.iter().find(|f| !used.contains(&f.to_string())) {
+        return Err(Error::new_spanned(&format, format!("Format does not mention field {}", missing)));
+    }
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::common::parse::Parse for #name #type_generics #where_clause {
+            fn parse(scanner: &mut ::common::parse::Scanner<'_>) -> ::common::parse::ParseResult<Self> {
+                #(#steps)*
+                Ok(#name { #(#fields),* })
+            }
+        }
+    })
+}
+
+// Whitespace in the format matches any run of whitespace, everything else must match exactly
+fn literal_steps(literal: &str) -> Vec<TokenStream> {
+    let mut steps = Vec::new();
+    let mut rest = literal;
+    while !rest.is_empty() {
+        let split = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        if split == 0 {
+            steps.push(quote! { scanner.ws(); });
+            rest = rest.trim_start();
+        } else {
+            let tag = &rest[..split];
+            steps.push(quote! { scanner.tag(#tag)?; });
+            rest = &rest[split..];
+        }
+    }
+    steps
+}
+
+#[cfg(test)]
+mod aoc_parse_tests {
+    use super::*;
+
+    fn field(name: &str, separator: Option<&str>) -> Segment {
+        Segment::Field { name: name.to_string(), separator: separator.map(str::to_string) }
+    }
+
+    #[test]
+    fn parse_format_splits_literals_and_fields() {
+        let segments = parse_format("Game {id}: {sets:sep('; ')}").unwrap();
+        assert_eq!(segments, vec![
+            Segment::Literal("Game ".to_string()),
+            field("id", None),
+            Segment::Literal(": ".to_string()),
+            field("sets", Some("; ")),
+        ]);
+    }
+
+    #[test]
+    fn parse_format_unescapes_braces() {
+        let segments = parse_format("{{{a}}}").unwrap();
+        assert_eq!(segments, vec![
+            Segment::Literal("{".to_string()),
+            field("a", None),
+            Segment::Literal("}".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn parse_format_when_malformed_returns_error() {
+        assert!(parse_format("{id").is_err());
+        assert!(parse_format("id}").is_err());
+        assert!(parse_format("{}").is_err());
+        assert!(parse_format("{a:split(',')}").is_err());
+        assert!(parse_format("{a:sep('')}").is_err());
+    }
+}
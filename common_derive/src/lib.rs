@@ -0,0 +1,21 @@
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod aoc_parse;
+mod tile_char;
+
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    aoc_parse::expand(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(TileChar, attributes(tile))]
+pub fn derive_tile_char(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    tile_char::expand(&input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,163 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::parse::ParseStream;
+use syn::{Attribute, Data, DeriveInput, Error, Fields, Ident, LitChar, Result, Token};
+
+struct TileVariant {
+    ident: Ident,
+    // The first character is used when rendering, the rest are accepted as aliases when parsing
+    parsed: Vec<LitChar>,
+    rendered: LitChar,
+}
+
+// Accepts #[tile('#')], #[tile('.', 'S')] and #[tile(render = 'O')] for variants that are only
+// ever produced by code, never read from input
+fn parse_tile_attribute(attribute: &Attribute) -> Result<(Vec<LitChar>, Option<LitChar>)> {
+    attribute.parse_args_with(|input: ParseStream| {
+        let mut parsed = Vec::new();
+        let mut rendered = None;
+        while !input.is_empty() {
+            if input.peek(Ident) {
+                let key: Ident = input.parse()?;
+                if key != "render" {
+                    return Err(Error::new_spanned(key, "Unknown tile option, expected render = '...'"));
+                }
+                input.parse::<Token![=]>()?;
+                rendered = Some(input.parse::<LitChar>()?);
+            } else {
+                parsed.push(input.parse::<LitChar>()?);
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok((parsed, rendered))
+    })
+}
+
+fn find_variants(input: &DeriveInput) -> Result<Vec<TileVariant>> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => return Err(Error::new_spanned(&input.ident, "TileChar only supports enums")),
+    };
+    let mut variants: Vec<TileVariant> = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(variant, "TileChar variants cannot carry data"));
+        }
+        let attribute = variant.attrs.iter().find(|a| a.path().is_ident("tile"))
+            .ok_or_else(|| Error::new_spanned(variant, "Missing #[tile('c')] attribute"))?;
+        let (parsed, rendered) = parse_tile_attribute(attribute)?;
+        let rendered = match (rendered, parsed.first()) {
+            (Some(rendered), _) => rendered,
+            (None, Some(first)) => first.clone(),
+            (None, None) => return Err(Error::new_spanned(attribute, "Tile attribute needs at least one character")),
+        };
+        for c in &parsed {
+            if variants.iter().any(|v| v.parsed.iter().any(|p| p.value() == c.value())) {
+                return Err(Error::new_spanned(c, format!("{:?} is already used by another variant", c.value())));
+            }
+        }
+        variants.push(TileVariant { ident: variant.ident.clone(), parsed, rendered });
+    }
+    // A render char that parses as another variant would not survive a round trip through text
+    for variant in &variants {
+        let rendered = variant.rendered.value();
+        let parsed_by = variants.iter()
+            .find(|v| v.ident != variant.ident && v.parsed.iter().any(|p| p.value() == rendered));
+        if let Some(other) = parsed_by {
+            let message = format!("{:?} is rendered by {} but parsed as {}", rendered, variant.ident, other.ident);
+            return Err(Error::new_spanned(&variant.rendered, message));
+        }
+    }
+    Ok(variants)
+}
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let variants = find_variants(input)?;
+    let parse_arms = variants.iter()
+        .filter(|v| !v.parsed.is_empty())
+        .map(|TileVariant { ident, parsed, .. }| quote! { #(#parsed)|* => Ok(#name::#ident), });
+    let render_arms = variants.iter()
+        .map(|TileVariant { ident, rendered, .. }| quote! { #name::#ident => #rendered, });
+    let render_arms_ref = render_arms.clone();
+
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::std::convert::TryFrom<char> for #name #type_generics #where_clause {
+            type Error = char;
+
+            fn try_from(c: char) -> ::std::result::Result<Self, char> {
+                match c {
+                    #(#parse_arms)*
+                    _ => Err(c),
+                }
+            }
+        }
+
+        impl #impl_generics ::std::convert::From<#name #type_generics> for char #where_clause {
+            fn from(tile: #name #type_generics) -> char {
+                match tile {
+                    #(#render_arms)*
+                }
+            }
+        }
+
+        impl #impl_generics ::std::fmt::Display for #name #type_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let c = match self {
+                    #(#render_arms_ref)*
+                };
+                write!(f, "{}", c)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tile_char_tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn find_variants_accepts_render_only_variants() {
+        let input: DeriveInput = parse_quote! {
+            enum Tile {
+                #[tile('.', 'S')]
+                Open,
+                #[tile(render = 'O')]
+                Visited,
+            }
+        };
+        let variants = find_variants(&input).unwrap();
+        assert_eq!(variants[0].rendered.value(), '.');
+        assert_eq!(variants[1].rendered.value(), 'O');
+    }
+
+    #[test]
+    fn find_variants_when_render_char_is_parsed_by_another_variant_returns_error() {
+        let input: DeriveInput = parse_quote! {
+            enum Card {
+                #[tile(render = 'J')]
+                Joker,
+                #[tile('J')]
+                Jack,
+            }
+        };
+        assert!(find_variants(&input).is_err());
+    }
+
+    #[test]
+    fn find_variants_when_char_is_parsed_twice_returns_error() {
+        let input: DeriveInput = parse_quote! {
+            enum Tile {
+                #[tile('#')]
+                Wall,
+                #[tile('.', '#')]
+                Open,
+            }
+        };
+        assert!(find_variants(&input).is_err());
+    }
+}
@@ -1,27 +1,24 @@
 use std::fmt;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-enum Direction { North, East, South, West }
+use common::bitset::BitSet;
+use common::geom::{Direction, Point};
+use common::grid::Grid;
+use common::polygon::Polygon;
+use common::search;
+use common::TileChar;
 
 struct Distances {
-    distances: Vec<Vec<Option<u32>>>,
-}
-impl Distances {
-    fn get_distance(&self, x: usize, y: usize) -> Option<u32> { self.distances[y][x] }
+    distances: Grid<Option<u32>>,
 }
 impl fmt::Debug for Distances {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut s = String::new();
-        for row in self.distances.iter() {
+        for row in self.distances.rows() {
             for distance in row.iter() {
-                if distance.is_none() {
-                    s.push_str(if f.alternate() { "     " } else { " " });
-                } else {
-                    if f.alternate() {
-                        s.push_str(&format!(" {:4}", distance.unwrap()));
-                    } else {
-                        s.push_str("#");
-                    }
+                match distance {
+                    None => s.push_str(if f.alternate() { "     " } else { " " }),
+                    Some(distance) if f.alternate() => s.push_str(&format!(" {:4}", distance)),
+                    Some(_) => s.push('#'),
                 }
             }
             s.push('\n');
@@ -30,27 +27,43 @@ impl fmt::Debug for Distances {
     }
 }
 
-#[derive(Copy, Clone)]
-struct Pipe {
-    directions: [Direction; 2],
+#[derive(Copy, Clone, PartialEq, TileChar)]
+enum Pipe {
+    #[tile('|')]
+    NorthSouth,
+    #[tile('-')]
+    EastWest,
+    #[tile('L')]
+    NorthEast,
+    #[tile('J')]
+    NorthWest,
+    #[tile('F')]
+    SouthEast,
+    #[tile('7')]
+    SouthWest,
 }
 impl Pipe {
-    fn parse(c: char) -> Pipe {
-        match c {
-            '|' => Pipe { directions: [Direction::North, Direction::South] },
-            '-' => Pipe { directions: [Direction::East, Direction::West] },
-            'L' => Pipe { directions: [Direction::North, Direction::East] },
-            'J' => Pipe { directions: [Direction::North, Direction::West] },
-            'F' => Pipe { directions: [Direction::East, Direction::South] },
-            '7' => Pipe { directions: [Direction::South, Direction::West] },
-            _ => panic!("Invalid pipe character: {}", c),
+    const ALL: [Pipe; 6] = [Pipe::NorthSouth, Pipe::EastWest, Pipe::NorthEast, Pipe::NorthWest, Pipe::SouthEast, Pipe::SouthWest];
+
+    fn directions(&self) -> [Direction; 2] {
+        match self {
+            Pipe::NorthSouth => [Direction::North, Direction::South],
+            Pipe::EastWest => [Direction::East, Direction::West],
+            Pipe::NorthEast => [Direction::North, Direction::East],
+            Pipe::NorthWest => [Direction::North, Direction::West],
+            Pipe::SouthEast => [Direction::East, Direction::South],
+            Pipe::SouthWest => [Direction::South, Direction::West],
         }
     }
 
-    fn has_north(&self) -> bool { self.directions.iter().any(|d| *d == Direction::North) }
-    fn has_east(&self) -> bool { self.directions.iter().any(|d| *d == Direction::East) }
-    fn has_south(&self) -> bool { self.directions.iter().any(|d| *d == Direction::South) }
-    fn has_west(&self) -> bool { self.directions.iter().any(|d| *d == Direction::West) }
+    fn connecting(a: Direction, b: Direction) -> Option<Pipe> {
+        Pipe::ALL.into_iter().find(|p| p.directions().contains(&a) && p.directions().contains(&b))
+    }
+
+    fn has_north(&self) -> bool { self.directions().contains(&Direction::North) }
+    fn has_east(&self) -> bool { self.directions().contains(&Direction::East) }
+    fn has_south(&self) -> bool { self.directions().contains(&Direction::South) }
+    fn has_west(&self) -> bool { self.directions().contains(&Direction::West) }
 }
 impl fmt::Debug for Pipe {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -67,186 +80,166 @@ impl fmt::Debug for Pipe {
         } else if self.has_south() && self.has_west() {
             write!(f, "╗")
         } else {
-            panic!("Invalid pipe: {:?}", self.directions)
+            panic!("Invalid pipe: {:?}", self.directions())
         }
     }
 }
 
-struct Grid {
-    start_x: usize,
-    start_y: usize,
-    pipes: Vec<Vec<Option<Pipe>>>,
+struct PipeGrid {
+    start: Point,
+    pipes: Grid<Option<Pipe>>,
 }
-impl fmt::Debug for Grid {
+impl fmt::Debug for PipeGrid {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut s = String::new();
-        for (y, row) in self.pipes.iter().enumerate() {
-            for (x, pipe) in row.iter().enumerate() {
-                if pipe.is_none() {
-                    s.push(' ');
-                    continue;
-                }
-                if x == self.start_x && y == self.start_y {
-                    s.push('S');
-                    continue;
-                }
-
+        for ((x, y), pipe) in self.pipes.iter() {
+            if pipe.is_none() {
+                s.push(' ');
+            } else if Point::from((x, y)) == self.start {
+                s.push('S');
+            } else {
                 s.push(format!("{:?}", pipe.unwrap()).chars().next().unwrap());
             }
-            s.push('\n');
+            if x == self.pipes.width() - 1 {
+                s.push('\n');
+            }
         }
         write!(f, "{}", s)
     }
 }
-impl Grid {
-    fn parse(lines: &Vec<String>) -> Grid {
-        let mut pipes = Vec::new();
-        let mut start_x: usize = 0;
-        let mut start_y: usize = 0;
-        for (y, line) in lines.iter().enumerate() {
-            let mut row = Vec::new();
-            for (x, c) in line.chars().enumerate() {
-                if c == 'S' {
-                    row.push(None);
-                    start_x = x;
-                    start_y = y;
-                    continue;
-                } else if c == '.' {
-                    row.push(None);
-                    continue;
-                }
-                row.push(Some(Pipe::parse(c)));
-            }
-            pipes.push(row);
-        }
-        let has_north = start_y > 0 && pipes[start_y - 1][start_x].is_some() &&
-            pipes[start_y - 1][start_x].unwrap().has_south();
-        let has_south = start_y < pipes.len() - 1 && pipes[start_y + 1][start_x].is_some() &&
-            pipes[start_y + 1][start_x].unwrap().has_north();
-        let has_west = start_x > 0 && pipes[start_y][start_x - 1].is_some() &&
-            pipes[start_y][start_x - 1].unwrap().has_east();
-        let has_east = start_x < pipes[start_y].len() - 1 && pipes[start_y][start_x + 1].is_some() &&
-            pipes[start_y][start_x + 1].unwrap().has_west();
-        if has_north && has_east {
-            pipes[start_y][start_x] = Some(Pipe{ directions: [Direction::North, Direction::East] });
-        } else if has_north && has_south {
-            pipes[start_y][start_x] = Some(Pipe{ directions: [Direction::North, Direction::South] });
-        } else if has_north && has_west {
-            pipes[start_y][start_x] = Some(Pipe{ directions: [Direction::North, Direction::West] });
-        } else if has_east && has_south {
-            pipes[start_y][start_x] = Some(Pipe{ directions: [Direction::East, Direction::South] });
-        } else if has_east && has_west {
-            pipes[start_y][start_x] = Some(Pipe{ directions: [Direction::East, Direction::West] });
-        } else if has_south && has_west {
-            pipes[start_y][start_x] = Some(Pipe{ directions: [Direction::South, Direction::West] });
-        } else {
+impl PipeGrid {
+    fn parse(lines: &[String]) -> PipeGrid {
+        let mut pipes = Grid::parse(lines, |c| match c {
+            '.' | 'S' => Some(None),
+            _ => Pipe::try_from(c).ok().map(Some),
+        }).unwrap();
+        let start = lines.iter()
+            .enumerate()
+            .find_map(|(y, line)| line.find('S').map(|x| Point::from((x, y))))
+            .expect("Missing starting position");
+        let connects = |direction: &Direction| pipes.get_at(start.step(*direction))
+            .is_some_and(|p| p.is_some_and(|p| p.directions().contains(&direction.opposite())));
+        let directions: Vec<Direction> = Direction::ALL.into_iter().filter(connects).collect();
+        if directions.len() != 2 {
             panic!("Invalid starting position");
         }
-        Grid { start_x, start_y, pipes }
+        let (x, y) = start.to_index().unwrap();
+        pipes.set(x, y, Pipe::connecting(directions[0], directions[1]));
+        PipeGrid { start, pipes }
     }
 
-    fn get_pipe(&self, x: usize, y: usize) -> Option<Pipe> { self.pipes[y][x] }
-
     fn calculate_distances(&self) -> Distances {
-        // use breadth first starting from start_x, start_y to calculate distance to all other pipes
-        let mut distances = vec![vec![None; self.pipes[0].len()]; self.pipes.len()];
-        let mut queue = Vec::new();
-        queue.push((self.start_x, self.start_y, 0));
-        while !queue.is_empty() {
-            let (x, y, distance) = queue.remove(0);
-            if distances[y][x].is_some() {
-                continue;
-            }
-            distances[y][x] = Some(distance);
-            if self.pipes[y][x].unwrap().has_west() {
-                queue.push((x - 1, y, distance + 1));
-            }
-            if self.pipes[y][x].unwrap().has_east() {
-                queue.push((x + 1, y, distance + 1));
-            }
-            if self.pipes[y][x].unwrap().has_north() {
-                queue.push((x, y - 1, distance + 1));
-            }
-            if self.pipes[y][x].unwrap().has_south() {
-                queue.push((x, y + 1, distance + 1));
+        // use breadth first starting from start to calculate distance to all other pipes
+        let connected = |point: &Point| match self.pipes.get_at(*point) {
+            Some(Some(pipe)) => pipe.directions().iter().map(|d| point.step(*d)).collect(),
+            _ => Vec::new(),
+        };
+        let result = search::bfs(self.start, connected, |_| false);
+        let mut distances = Grid::new(self.pipes.width(), self.pipes.height(), None);
+        for (point, distance) in result.distances {
+            if let Some(cell) = distances.get_at_mut(point) {
+                *cell = Some(distance as u32);
             }
         }
         Distances { distances }
     }
 
-    fn simplify(&self, distances: &Distances) -> Grid {
-        let mut pipes = Vec::new();
-        for (y, row) in self.pipes.iter().enumerate() {
-            let mut new_row = Vec::new();
-            for (x, pipe) in row.iter().enumerate() {
-                if distances.get_distance(x, y).is_some() {
-                    new_row.push(*pipe);
-                    continue;
-                }
-                new_row.push(None);
+    fn cell_index(&self, point: Point) -> usize {
+        let (x, y) = point.to_index().unwrap();
+        y * self.pipes.width() + x
+    }
+
+    // Drops every pipe that is not part of the loop
+    fn simplify(&self, pipe_loop: &Polygon) -> PipeGrid {
+        let loop_cells: BitSet = pipe_loop.vertices().iter().map(|p| self.cell_index(*p)).collect();
+        let mut pipes = self.pipes.clone();
+        for (x, y) in self.pipes.positions() {
+            if !loop_cells.contains(self.cell_index(Point::from((x, y)))) {
+                pipes.set(x, y, None);
             }
-            pipes.push(new_row);
         }
-        Grid { start_x: self.start_x, start_y: self.start_y, pipes }
+        PipeGrid { start: self.start, pipes }
     }
 
-
-    fn is_inside(&self, x: usize, y: usize) -> bool {
-        if x == 0 || y == 0 || x == self.pipes[0].len() - 1 || y == self.pipes.len() - 1 {
-            return false;
-        }
-        if self.pipes[y][x].is_some() {
-            return false;
+    fn trace_loop(&self) -> Polygon {
+        // Every loop tile becomes a vertex, the straight runs are just collinear vertices
+        let mut vertices = vec![self.start];
+        let mut direction = self.pipes.get_at(self.start).unwrap().unwrap().directions()[0];
+        let mut current = self.start.step(direction);
+        while current != self.start {
+            vertices.push(current);
+            let pipe = self.pipes.get_at(current).unwrap().expect("Loop is broken");
+            direction = *pipe.directions().iter().find(|d| **d != direction.opposite()).unwrap();
+            current = current.step(direction);
         }
-
-        let mut pipes_str = self.pipes[y][0..x].iter()
-            .map(|p| {
-                match p {
-                    None => ' ',
-                    Some(p) => format!("{:?}", p).chars().next().unwrap(),
-                }
-            }).collect::<String>();
-
-        pipes_str = pipes_str.replace(" ", "");
-        pipes_str = pipes_str.replace("═", "");
-        pipes_str = pipes_str.replace("╚╝", "");
-        pipes_str = pipes_str.replace("╔╗", "");
-        pipes_str = pipes_str.replace("╔╝", "║");
-        pipes_str = pipes_str.replace("╚╗", "║");
-
-        let pipes_west = pipes_str.len();
-
-        pipes_west % 2 == 1
+        Polygon::new(vertices)
     }
 }
 
-
-
 fn main() {
-    let is_part_one = common::is_part_one();
+    let _is_part_one = common::is_part_one();
     let input_file_path = common::get_input_file_path();
-    let mut grid = Grid::parse(&common::read_file_line_by_line(&input_file_path));
+    let mut grid = PipeGrid::parse(&common::read_file_line_by_line(&input_file_path));
     let distances = grid.calculate_distances();
 
     println!("grid:\n{:#?}", grid);
     println!("distances:\n{:?}", distances);
-    println!("Max Distance: {:?}", distances.distances.iter().flatten().max().unwrap());
-
-    grid = grid.simplify(&distances);
-
-    let mut tiles_inside: u32 = 0;
-    for (y, row) in grid.pipes.iter().enumerate() {
-        for (x, pipe) in row.iter().enumerate() {
-            if grid.is_inside(x, y) {
-                tiles_inside += 1;
-                print!("!");
-            } else if pipe.is_some() {
-                print!("{:?}", pipe.unwrap());
-            } else {
-                print!(" ");
+    println!("Max Distance: {:?}", distances.distances.iter().filter_map(|(_, d)| *d).max().unwrap());
+
+    let pipe_loop = grid.trace_loop();
+    grid = grid.simplify(&pipe_loop);
+
+    for row in grid.pipes.rows() {
+        for pipe in row.iter() {
+            match pipe {
+                Some(pipe) => print!("{:?}", pipe),
+                None => print!(" "),
             }
         }
         println!();
     }
-    println!("Tiles inside: {}", tiles_inside);
+    println!("Tiles inside: {}", pipe_loop.interior_points());
+}
+
+#[cfg(test)]
+mod pipe_loop_tests {
+    use super::*;
+    use common::bitgrid::BitGrid;
+
+    // Scans each row once, a tile is inside when an odd number of north-going loop pipes lie to
+    // its left. Only valid on a simplified grid, where every remaining pipe is part of the loop.
+    fn inside_cells(grid: &PipeGrid) -> BitGrid {
+        let mut inside_cells = BitGrid::new(grid.pipes.width(), grid.pipes.height());
+        for (y, row) in grid.pipes.rows().enumerate() {
+            let mut inside = false;
+            for (x, pipe) in row.iter().enumerate() {
+                match pipe {
+                    Some(pipe) if pipe.has_north() => inside = !inside,
+                    Some(_) => {}
+                    None => inside_cells.set(x, y, inside),
+                }
+            }
+        }
+        inside_cells
+    }
+
+    #[test]
+    fn row_scan_agrees_with_picks_theorem() {
+        let lines = [
+            "...........",
+            ".S-------7.",
+            ".|F-----7|.",
+            ".||.....||.",
+            ".||.....||.",
+            ".|L-7.F-J|.",
+            ".|..|.|..|.",
+            ".L--J.L--J.",
+            "...........",
+        ].map(String::from);
+        let grid = PipeGrid::parse(&lines);
+        let pipe_loop = grid.trace_loop();
+        let grid = grid.simplify(&pipe_loop);
+        assert_eq!(pipe_loop.interior_points(), 4);
+        assert_eq!(inside_cells(&grid).count_ones() as i128, pipe_loop.interior_points());
+    }
 }
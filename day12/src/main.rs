@@ -1,47 +1,38 @@
-use memoize::memoize;
+use common::memo::Memo;
+use common::TileChar;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TileChar)]
 enum Condition {
+    #[tile('.')]
     Operational,
+    #[tile('#')]
     Damaged,
+    #[tile('?')]
     Unknown,
 }
 
-impl Condition {
-    fn parse(c: char) -> Self {
-        match c {
-            '.' => Condition::Operational,
-            '#' => Condition::Damaged,
-            '?' => Condition::Unknown,
-            _ => panic!("Invalid condition: {}", c),
-        }
-    }
-}
-
-fn debug_log(depth: usize, debug: bool, message: &str) {
-    if debug {
-        println!("{}{}", " ".repeat(depth), message);
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct DataSet {
     spring_conditions: Vec<Condition>,
-    damaged_sets: Vec<u32>,
+    damaged_sets: Vec<usize>,
 }
 
+// How far through the springs and the damaged sets a partial arrangement has got
+type Position = (usize, usize);
+
 impl DataSet {
     fn parse(line: &str, unfold: bool) -> Self {
-        let mut parts = line.split(" ");
+        let mut parts = line.split(' ');
         let times = if unfold { 5 } else { 1 };
-        let spring_conditions = std::iter::repeat(parts.next().unwrap())
-            .take(times).collect::<Vec<&str>>()
+        let spring_conditions = std::iter::repeat_n(parts.next().unwrap(), times)
+            .collect::<Vec<&str>>()
             .join("?").chars()
-            .map(|c| Condition::parse(c)).collect::<Vec<Condition>>();
-        let damaged_sets = std::iter::repeat(parts.next().unwrap())
-            .take(times).collect::<Vec<&str>>()
-            .join(",").split(",")
-            .map(|s| s.parse::<u32>().unwrap()).collect::<Vec<u32>>();
+            .map(|c| Condition::try_from(c).unwrap_or_else(|c| panic!("Invalid condition: {}", c)))
+            .collect::<Vec<Condition>>();
+        let damaged_sets = std::iter::repeat_n(parts.next().unwrap(), times)
+            .collect::<Vec<&str>>()
+            .join(",").split(',')
+            .map(|s| s.parse::<usize>().unwrap()).collect::<Vec<usize>>();
 
         Self {
             spring_conditions,
@@ -49,86 +40,42 @@ impl DataSet {
         }
     }
 
-    fn trim_operational(&self) -> Self {
-        let mut spring_conditions = self.spring_conditions.clone();
-        while spring_conditions.len() > 0 && *spring_conditions.first().unwrap() == Condition::Operational {
-            spring_conditions.remove(0);
-        }
-        while spring_conditions.len() > 0 && *spring_conditions.last().unwrap() == Condition::Operational {
-            spring_conditions.pop();
-        }
-        Self {
-            spring_conditions,
-            damaged_sets: self.damaged_sets.clone(),
-        }
-    }
-
-    fn can_fit_next_damaged_set(&self) -> bool {
-        self.spring_conditions.len() >= self.damaged_sets[0] as usize
-            && self.spring_conditions[0..self.damaged_sets[0] as usize].iter().all(|c| *c != Condition::Operational)
-            && (self.spring_conditions.len() == self.damaged_sets[0] as usize
-                || self.spring_conditions[self.damaged_sets[0] as usize] != Condition::Damaged)
-    }
-
-    fn has_damaged_set_but_no_springs(&self) -> bool {
-        self.spring_conditions.len() == 0 && self.damaged_sets.len() > 0
+    // The cache only lives for this data set, so lines never see each other's entries
+    fn get_arrangements(&self) -> u64 {
+        let mut memo = Memo::new();
+        self.count_from(&mut memo, (0, 0))
     }
 
-    fn has_damaged_springs(&self) -> bool {
-        self.spring_conditions.iter().any(|s| *s == Condition::Damaged)
-    }
-}
-
-#[memoize]
-fn get_arrangements(data_set: DataSet) -> u64 {
-    // Handle the end cases where we're out of either springs or damaged sets.
-    if data_set.spring_conditions.len() == 0 {
-        if data_set.damaged_sets.len() == 0 {
-            1 // No springs, and no damaged sets, this arrangement is good
-        } else {
-            0 // No springs, but we have damaged sets, this arrangement is bad
+    fn count_from(&self, memo: &mut Memo<Position, u64>, position: Position) -> u64 {
+        let (spring, set) = position;
+        if spring == self.spring_conditions.len() {
+            // Out of springs, the arrangement is only good if every damaged set was placed
+            return (set == self.damaged_sets.len()) as u64;
         }
-    } else if data_set.damaged_sets.len() == 0 {
-        if data_set.has_damaged_springs() {
-            0  // We have no damaged sets, but do still have damaged springs, this arrangement is bad
-        } else {
-            1 // We have no damaged sets, and no damaged springs, this arrangement is good
-        }
-    } else {
-        // If we get here then there are non-operational springs, and at least one damaged set left
-        if data_set.can_fit_next_damaged_set() {
-            let mut arrangements: u64 = 0;
-
-            // Calculate potential arrangements where we assume the next spring is damaged
-            if data_set.spring_conditions.len() == data_set.damaged_sets[0] as usize {
-                // If the next damaged set fits perfectly, then we can assume it is operational
-                arrangements += get_arrangements(DataSet {
-                    spring_conditions: Vec::new(),
-                    damaged_sets: data_set.damaged_sets[1..].to_vec(),
-                }.trim_operational());
-            } else {
-                arrangements += get_arrangements(DataSet {
-                    spring_conditions: data_set.spring_conditions[data_set.damaged_sets[0] as usize + 1..].to_vec(),
-                    damaged_sets: data_set.damaged_sets[1..].to_vec(),
-                }.trim_operational());
-            }
-
-            // If the next spring could be operational, also calculate where we assume it is
-            if data_set.spring_conditions[0] == Condition::Unknown {
-                arrangements += get_arrangements(DataSet {
-                    spring_conditions: data_set.spring_conditions[1..].to_vec(),
-                    damaged_sets: data_set.damaged_sets.clone(),
-                }.trim_operational());
+        memo.get_or_insert_with(position, |memo| match self.spring_conditions[spring] {
+            Condition::Operational => self.count_from(memo, (spring + 1, set)),
+            Condition::Damaged => self.count_with_set_at(memo, position),
+            Condition::Unknown => {
+                self.count_from(memo, (spring + 1, set)) + self.count_with_set_at(memo, position)
             }
+        })
+    }
 
-            arrangements
-        } else if data_set.spring_conditions[0] == Condition::Unknown {
-            get_arrangements(DataSet {
-                spring_conditions: data_set.spring_conditions[1..].to_vec(),
-                damaged_sets: data_set.damaged_sets.clone(),
-            }.trim_operational())
-        } else {
-            0
+    // Places the next damaged set starting at this spring, followed by an operational spring
+    fn count_with_set_at(&self, memo: &mut Memo<Position, u64>, position: Position) -> u64 {
+        let (spring, set) = position;
+        let Some(length) = self.damaged_sets.get(set) else {
+            return 0; // A damaged spring with no damaged sets left, this arrangement is bad
+        };
+        let end = spring + length;
+        if end > self.spring_conditions.len()
+            || self.spring_conditions[spring..end].contains(&Condition::Operational) {
+            return 0;
+        }
+        match self.spring_conditions.get(end) {
+            None => self.count_from(memo, (end, set + 1)),
+            Some(Condition::Damaged) => 0, // The set would run on past its length
+            Some(_) => self.count_from(memo, (end + 1, set + 1)),
         }
     }
 }
@@ -141,8 +88,7 @@ fn main() {
     let mut total_arrangements: u64 = 0;
     for line in lines {
         let data_set = DataSet::parse(line, !is_part_one);
-        let arrangements = get_arrangements(data_set.trim_operational());
-        total_arrangements += arrangements;
+        total_arrangements += data_set.get_arrangements();
     }
 
     println!("Total arrangements: {}", total_arrangements);
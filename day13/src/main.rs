@@ -1,88 +1,37 @@
-fn find_reflection_index(values: &Vec<u64>, with_smudge: bool) -> Option<u64> {
-    for center in 1..values.len() {
-        let mut found_center = true;
-        let mut has_smudge = false;
-        for right in center..values.len() {
-            let offset = right - center + 1;
-            if offset > center {
-                if !with_smudge || has_smudge {
-                    return Some(center as u64)
-                } else {
-                    found_center = false;
-                    break;
-                };
-            }
-            let left = center - offset;
-            if values[left] != values[right] {
-                // if the bit-xor is a power of two, then there is exactly one tile/bit different
-                if with_smudge && !has_smudge && (values[left] ^ values[right]).is_power_of_two()  {
-                    has_smudge = true;
-                } else {
-                    found_center = false;
-                    break;
-                }
-            }
-        }
-        if found_center && (!with_smudge || has_smudge) {
-            return Some(center as u64);
-        }
-    }
-    None
+use common::bitgrid::{BitGrid, BitRow};
+use common::grid::Grid;
+
+// The reflection sits where the mirrored pairs of lines differ by exactly the smudge count
+fn find_reflection_index(lines: &[BitRow], with_smudge: bool) -> Option<u64> {
+    let smudges = if with_smudge { 1 } else { 0 };
+    (1..lines.len())
+        .find(|center| {
+            lines[..*center].iter().rev()
+                .zip(lines[*center..].iter())
+                .map(|(left, right)| left.hamming_distance(right))
+                .sum::<usize>() == smudges
+        })
+        .map(|center| center as u64)
 }
 
 struct Field {
-    cells: Vec<Vec<char>>,
-    row_values: Vec<u64>,
-    col_values: Vec<u64>,
+    cells: BitGrid,
 }
 
 impl Field {
-    fn parse(lines: &Vec<String>) -> Field {
-        let mut cells = Vec::new();
-        let mut row_values = Vec::new();
-        let mut col_values = Vec::new();
-
-        for line in lines {
-            let mut row = Vec::new();
-            for c in line.chars() {
-                row.push(c);
-            }
-            cells.push(row);
-        }
-
-        for i in 0..cells.len() {
-            let mut row_value = 0;
-            for j in 0..cells[i].len() {
-                if cells[i][j] == '#' {
-                    row_value += 1 << j;
-                }
-            }
-            row_values.push(row_value);
-        }
-
-        for j in 0..cells[0].len() {
-            let mut col_value = 0;
-            for i in 0..cells.len() {
-                if cells[i][j] == '#' {
-                    col_value += 1 << i;
-                }
-            }
-            col_values.push(col_value);
-        }
-
+    fn parse(lines: &[String]) -> Field {
+        let grid = Grid::parse(lines, Some).unwrap();
         Field {
-            cells,
-            row_values,
-            col_values,
+            cells: BitGrid::from_grid(&grid, |c| *c == '#'),
         }
     }
 
     fn find_horizontal_reflection_index(&self, with_smudge: bool) -> Option<u64> {
-        find_reflection_index(&self.col_values, with_smudge)
+        find_reflection_index(self.cells.columns(), with_smudge)
     }
 
     fn find_vertical_reflection_index(&self, with_smudge: bool) -> Option<u64> {
-        find_reflection_index(&self.row_values, with_smudge)
+        find_reflection_index(self.cells.rows(), with_smudge)
     }
 }
 
@@ -92,7 +41,7 @@ fn main() {
     let lines = &common::read_file_line_by_line(&input_file_path);
 
     let fields = lines.split(|line| line.is_empty())
-        .map(|lines| Field::parse(&lines.to_vec()));
+        .map(Field::parse);
 
     for (i, field) in fields.clone().enumerate() {
         if let Some(index) = field.find_horizontal_reflection_index(!is_part_one) {
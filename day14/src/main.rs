@@ -1,228 +1,124 @@
 use std::fmt::{Debug, Formatter, Result};
-use std::ops::Mul;
 
-const OPEN_CHAR: char = '.';
-const CUBE_CHAR: char = '#';
-const ROUND_CHAR: char = 'O';
+use common::cycle::{self, Simulation};
+use common::geom::{Direction, Point};
+use common::grid::{Grid, Transform};
+use common::TileChar;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, TileChar)]
 enum Tile {
+    #[tile('.')]
     Open,
+    #[tile('#')]
     Cube,
+    #[tile('O')]
     Round,
-    Outside,
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Offset {
-    x: i32,
-    y: i32,
-}
-
-impl Mul<i32> for Offset {
-    type Output = Self;
-    fn mul(self, other: i32) -> Self {
-        Self {
-            x: self.x * other,
-            y: self.y * other,
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-impl Direction {
-    fn get_offset(&self) -> Offset {
-        match self {
-            Direction::Up => Offset { x: 0, y: -1 },
-            Direction::Down => Offset { x: 0, y: 1 },
-            Direction::Left => Offset { x: -1, y: 0 },
-            Direction::Right => Offset { x: 1, y: 0 },
+fn tilt_north(tiles: &mut Grid<Tile>) {
+    for x in 0..tiles.width() {
+        let mut destination = 0;
+        for y in 0..tiles.height() {
+            match tiles[(x, y)] {
+                Tile::Cube => destination = y + 1,
+                Tile::Round => {
+                    tiles.set(x, y, Tile::Open);
+                    tiles.set(x, destination, Tile::Round);
+                    destination += 1;
+                }
+                Tile::Open => {}
+            }
         }
     }
 }
 
+#[derive(PartialEq)]
 struct Platform {
-    tiles: Vec<Tile>,
-    width: i32,
-    height: i32,
+    tiles: Grid<Tile>,
 }
 
 impl Platform {
-    fn parse(lines: &Vec<String>) -> Platform {
-        let mut tiles = Vec::new();
-
-        for line in lines {
-            for c in line.chars() {
-                tiles.push(match c {
-                    OPEN_CHAR => Tile::Open,
-                    CUBE_CHAR => Tile::Cube,
-                    ROUND_CHAR => Tile::Round,
-                    _ => panic!("Invalid tile char: {}", c),
-                });
-            }
-        }
-
-        if tiles.len() % lines.len() != 0 {
-            panic!("Invalid platform, must be rectangular");
-        }
-
-        let width = tiles.len() as i32 / lines.len() as i32;
-        let height = lines.len() as i32;
+    fn parse(lines: &[String]) -> Platform {
+        let tiles = Grid::parse(lines, |c| Tile::try_from(c).ok())
+            .unwrap_or_else(|e| panic!("Invalid platform: {}", e));
 
         Platform {
             tiles,
-            width,
-            height,
         }
     }
 
-    fn get_tile(&self, x: i32, y: i32) -> Tile {
-        if x < 0 || x >= self.width || y < 0 || y >= self.height {
-            return Tile::Outside;
-        }
-        let index = self.get_tile_index(x, y);
-        if index >= self.tiles.len() {
-            return Tile::Outside;
-        }
-        self.tiles[index]
-    }
-
-    fn get_tile_index(&self, x: i32, y: i32) -> usize {
-        (y * self.width + x) as usize
+    fn get_tile(&self, point: Point) -> Option<Tile> {
+        self.tiles.get_at(point).copied()
     }
 
     fn tilt(&self, direction: Direction) -> Self {
-        let mut new_tiles = self.tiles.clone();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.get_tile(x, y) == Tile::Round {
-                    let tile_index = self.get_tile_index(x, y);
-                    new_tiles[tile_index] = Tile::Open;
-                }
-            }
-        }
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let tile_index = self.get_tile_index(x, y);
-                let tile = self.get_tile(x, y);
-                if tile != Tile::Round {
-                    continue;
-                }
-
-                let destination = self.find_roll_destination(x, y, direction);
-                let destination_index = self.get_tile_index(destination.0, destination.1);
-
-                // println!("{} {} -> {} {}", x, y, destination.0, destination.1);
-
-                new_tiles[destination_index] = Tile::Round;
-            }
-        }
+        // Turn the platform so the requested direction points north, tilt, then turn it back
+        let transform = match direction {
+            Direction::North => Transform::Identity,
+            Direction::South => Transform::FlipVertical,
+            Direction::West => Transform::Transpose,
+            Direction::East => Transform::AntiTranspose,
+        };
+        let mut tiles = self.tiles.transformed(transform);
+        tilt_north(&mut tiles);
         Self {
-            tiles: new_tiles,
-            width: self.width,
-            height: self.height,
+            tiles: tiles.transformed(transform.inverse()),
         }
     }
 
-    fn find_roll_destination(&self, x: i32, y: i32, direction: Direction) -> (i32, i32) {
-        let offset = direction.get_offset();
-        let mut x = x + offset.x;
-        let mut y = y + offset.y;
-        let mut round_count = 0;
-        loop {
-            let tile = self.get_tile(x, y);
-            if tile == Tile::Round {
-                round_count += 1;
-            }
-            if tile == Tile::Cube  || tile == Tile::Outside {
-                x -= offset.x;
-                y -= offset.y;
-                break;
-            }
-            x += offset.x;
-            y += offset.y;
-        }
-        let final_offset = offset * round_count;
-        (x - final_offset.x, y - final_offset.y)
-    }
-
-    fn calculate_load(&self, x: i32, y: i32, direction: Direction) -> i32 {
+    fn calculate_load(&self, point: Point, direction: Direction) -> i64 {
         let mut load = 0;
-        let mut x = x;
-        let mut y = y;
-        loop {
-            let tile = self.get_tile(x, y);
-            if tile != Tile::Outside {
-                load += 1;
-                x -= direction.get_offset().x;
-                y -= direction.get_offset().y;
-            } else {
-                break;
-            }
+        let mut point = point;
+        while self.get_tile(point).is_some() {
+            load += 1;
+            point -= direction.to_vector();
         }
         load
     }
 
-    fn calculate_all_load(&self, direction: Direction) -> i32 {
+    fn calculate_all_load(&self, direction: Direction) -> i64 {
         let mut load = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let tile = self.get_tile(x, y);
-                if tile != Tile::Round {
-                    continue;
-                }
-                load += self.calculate_load(x, y, direction);
+        for (position, tile) in self.tiles.iter() {
+            if *tile != Tile::Round {
+                continue;
             }
+            load += self.calculate_load(Point::from(position), direction);
         }
         load
     }
 
     fn spin(&self) -> Self {
-        let mut result = self.tilt(Direction::Up);
+        let mut result = self.tilt(Direction::North);
         // println!("{:?}", result);
-        result = result.tilt(Direction::Left);
+        result = result.tilt(Direction::West);
         // println!("{:?}", result);
-        result = result.tilt(Direction::Down);
+        result = result.tilt(Direction::South);
         // println!("{:?}", result);
-        result.tilt(Direction::Right)
+        result.tilt(Direction::East)
     }
 
     fn get_hash(&self) -> u64 {
         let mut hash: u64 = 0;
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let tile = self.get_tile(x, y);
-                if tile == Tile::Round {
-                    hash += (y * self.width + x) as u64;
-                    hash *= 17;
-                    hash %= 1000000007;
-                }
+        for ((x, y), tile) in self.tiles.iter() {
+            if *tile == Tile::Round {
+                hash += (y * self.tiles.width() + x) as u64;
+                hash *= 17;
+                hash %= 1000000007;
             }
         }
         hash
     }
 }
 
+impl Simulation for Platform {
+    fn step(&self) -> Self {
+        self.spin()
+    }
+}
+
 impl Debug for Platform {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                match self.get_tile(x, y) {
-                    Tile::Open => write!(f, "{}", OPEN_CHAR),
-                    Tile::Cube => write!(f, "{}", CUBE_CHAR),
-                    Tile::Round => write!(f, "{}", ROUND_CHAR),
-                    Tile::Outside => write!(f, " "),
-                }?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", self.tiles)
     }
 }
 
@@ -235,36 +131,15 @@ fn main() {
     println!("{:?}", platform);
 
     if is_part_one {
-        let tilted = platform.tilt(Direction::Down);
+        let tilted = platform.tilt(Direction::South);
         println!("{:?}", tilted);
-        println!("Load: {}", tilted.calculate_all_load(Direction::Up));
+        println!("Load: {}", tilted.calculate_all_load(Direction::North));
     } else {
-        let target_cycles = 1_000_000_000;
-        let mut hashes: Vec<u64> = Vec::new();
-        hashes.push(platform.get_hash());
-        let mut cycles: u64 = 0;
-        let mut spun = platform;
-        let mut cycle_length = 0;
-        loop {
-            cycles += 1;
-            spun = spun.spin();
-            let hash = spun.get_hash();
-            let found_cycle = hashes.iter().position(|&r| r == hash);
-            hashes.push(hash);
-            if let Some(cycle_start) = found_cycle {
-                cycle_length = cycles - cycle_start as u64;
-                println!("Loop found from {} - {}", cycle_start, cycles);
-                break;
-            }
-        }
-
-        let remaining_cycles = (target_cycles - cycles) % cycle_length;
-        println!("Can skip {} cycles, {} remaining...", target_cycles - (remaining_cycles + cycles), remaining_cycles);
-        for _ in 0..remaining_cycles {
-            spun = spun.spin();
-        }
+        let cycle = cycle::detect_by_key(platform, Platform::get_hash);
+        println!("Loop found from {} - {}", cycle.start, cycle.start + cycle.length);
+        let spun = cycle.state_after(1_000_000_000);
         println!("{:?}", spun);
 
-        println!("Load: {}", spun.calculate_all_load(Direction::Up));
+        println!("Load: {}", spun.calculate_all_load(Direction::North));
     }
 }
@@ -1,31 +1,20 @@
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use common::geom::Direction;
+use common::TileChar;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, TileChar)]
 enum Tile {
-    Empty, // .
-    VerticalSplitter, // |
-    HorizontalSplitter, // -
-    RightMirror, // /
-    LeftMirror, // \
+    #[tile('.')]
+    Empty,
+    #[tile('|')]
+    VerticalSplitter,
+    #[tile('-')]
+    HorizontalSplitter,
+    #[tile('/')]
+    RightMirror,
+    #[tile('\\')]
+    LeftMirror,
 }
 impl Tile {
-    fn from_char(c: char) -> Tile {
-        match c {
-            '.' => Tile::Empty,
-            '|' => Tile::VerticalSplitter,
-            '-' => Tile::HorizontalSplitter,
-            '/' => Tile::RightMirror,
-            '\\' => Tile::LeftMirror,
-            _ => panic!("Unknown tile: {}", c),
-        }
-    }
-
     fn get_new_directions(&self, direction: Direction) -> Vec<Direction> {
         let mut new_directions: Vec<Direction> = Vec::new();
 
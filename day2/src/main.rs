@@ -1,3 +1,6 @@
+use common::parse::{self, Parse, ParseResult, Scanner};
+use common::AocParse;
+
 #[derive(Debug)]
 struct CubeSet {
     red: u32,
@@ -5,52 +8,41 @@ struct CubeSet {
     green: u32,
 }
 
-impl CubeSet {
-    fn parse(line: &str) -> CubeSet {
-        let mut red: u32 = 0;
-        let mut blue: u32 = 0;
-        let mut green: u32 = 0;
-        let parts: Vec<&str> = line.split(", ").collect();
-
-        for part in parts {
-            let part_parts: Vec<&str> = part.split(" ").collect();
-            let value = part_parts[0].parse().unwrap();
-            let color = part_parts[1];
-            match color {
-                "red" => red = value,
-                "blue" => blue = value,
-                "green" => green = value,
-                _ => panic!("Unknown color: {}", color),
-            }
-        }
-
-        CubeSet { red, blue, green }
+impl Parse for CubeSet {
+    fn parse(scanner: &mut Scanner) -> ParseResult<CubeSet> {
+        let mut cube_set = CubeSet { red: 0, blue: 0, green: 0 };
+        scanner.sep_by(", ", |s| {
+            let value: u32 = s.int()?;
+            s.ws();
+            let count = s.expect("red, blue or green", |s| match s.ident()? {
+                "red" => Ok(&mut cube_set.red),
+                "blue" => Ok(&mut cube_set.blue),
+                "green" => Ok(&mut cube_set.green),
+                _ => s.error("a colour"),
+            })?;
+            *count = value;
+            Ok(())
+        })?;
+        Ok(cube_set)
     }
+}
 
+impl CubeSet {
     fn power(&self) -> u32 {
         self.red * self.blue * self.green
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, AocParse)]
+#[aoc(format = "Game {game}: {cube_sets:sep('; ')}")]
 struct Game {
     game: u32,
     cube_sets: Vec<CubeSet>,
 }
 
 impl Game {
-    fn parse(line: &str) -> Game {
-        // split line on ": " to get the game and cube sets
-        let parts: Vec<&str> = line.split(": ").collect();
-        // trim "Game " from beginning of part[0] to get game number
-        let game: u32 = parts[0][5..].parse().unwrap();
-        // split part[1] on "; " to get the cube sets
-        let cube_sets: Vec<&str> = parts[1].split("; ").collect();
-
-        Game {
-            game,
-            cube_sets: cube_sets.iter().map(|x| CubeSet::parse(x)).collect()
-        }
+    fn from_line(line: &str) -> Game {
+        parse::parse_line(line).unwrap_or_else(|e| panic!("Invalid game {:?}: {}", line, e))
     }
     fn possible(&self, all_cubes: CubeSet) -> bool {
         self.cube_sets.iter().all(|x| {
@@ -82,7 +74,7 @@ fn main() {
     let is_part_one = common::is_part_one();
     let input_file_path = common::get_input_file_path();
     let lines: Vec<String> = common::read_file_line_by_line(&input_file_path);
-    let games = lines.iter().map(|l| Game::parse(l));
+    let games = lines.iter().map(|l| Game::from_line(l));
 
     if is_part_one {
         let mut possible_games_sum: u32 = 0;
@@ -1,10 +1,37 @@
+use common::graph::min_cut;
+use common::graph::Graph;
+use common::parse;
+
+fn parse_wiring(lines: &[String]) -> Graph<String> {
+    let mut graph = Graph::new(false);
+    for line in lines {
+        let (component, connections) = parse::parse_all(line, |s| {
+            let component = s.ident()?;
+            s.tag(":")?.ws();
+            let connections = s.sep_by(" ", |s| s.ident())?;
+            Ok((component, connections))
+        }).unwrap_or_else(|e| panic!("Invalid wiring {:?}: {}", line, e));
+        for connection in connections {
+            graph.add_edge(component.to_string(), connection.to_string());
+        }
+    }
+    graph
+}
+
 fn main() {
     let is_part_one = common::is_part_one();
     let input_file_path = common::get_input_file_path();
     let lines = &common::read_file_line_by_line(&input_file_path);
 
-    println!("TODO: Complete puzzle (part {}) using file: {}",
-             if is_part_one { 1 } else { 2 },
-             input_file_path
-    );
+    if !is_part_one {
+        println!("Day 25 has no second part");
+        return;
+    }
+
+    let graph = parse_wiring(lines);
+    let cut = min_cut::stoer_wagner(&graph).expect("Need at least two components to split");
+    println!("Cut {} wires: {:?}", cut.weight, cut.cut_edges);
+    let (left, right) = cut.partitions;
+    println!("Group sizes: {} and {}", left.len(), right.len());
+    println!("Result: {}", left.len() * right.len());
 }
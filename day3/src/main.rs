@@ -1,31 +1,27 @@
+use common::geom::{Point, Vector};
+
 #[derive(Debug)]
 struct PartNumber {
     value: u32,
-    line: usize,
-    column: usize
+    position: Point,
 }
 
 impl PartNumber {
-    fn width(&self) -> usize {
-        self.value.to_string().len()
+    fn width(&self) -> i64 {
+        self.value.to_string().len() as i64
     }
 
     fn is_adjacent(&self, symbol: &Symbol) -> bool {
-        if self.line < symbol.line - 1 || self.line > symbol.line + 1 {
-            false
-        } else if self.column + self.width() - 1 < symbol.column - 1 || self.column > symbol.column + 1 {
-            false
-        } else {
-            true
-        }
+        (0..self.width()).any(|i| {
+            (self.position + Vector::new(i, 0)).chebyshev_distance(&symbol.position) <= 1
+        })
     }
 }
 
 #[derive(Debug)]
 struct Symbol {
     value: char,
-    line: usize,
-    column: usize
+    position: Point,
 }
 
 fn main() {
@@ -35,41 +31,36 @@ fn main() {
     let mut part_numbers: Vec<PartNumber> = Vec::new();
     let mut symbols: Vec<Symbol> = Vec::new();
 
-    let mut line_number: usize = 0;
     let mut number: u32 = 0;
     let mut number_column: usize = 0;
 
-    for line in common::read_file_line_by_line(&input_file_path) {
+    for (line_number, line) in common::read_file_line_by_line(&input_file_path).iter().enumerate() {
         for (column, character) in line.chars().enumerate() {
-            if character.is_digit(10) {
+            if character.is_ascii_digit() {
                 if number == 0 { number_column = column; }
                 number *= 10;
                 number += character.to_digit(10).unwrap();
             } else if number > 0 {
                 part_numbers.push(PartNumber {
                     value: number,
-                    line: line_number,
-                    column: number_column
+                    position: Point::from((number_column, line_number)),
                 });
                 number = 0;
             }
-            if !character.is_digit(10) && character != '.' {
+            if !character.is_ascii_digit() && character != '.' {
                 symbols.push(Symbol {
                     value: character,
-                    line: line_number,
-                    column
+                    position: Point::from((column, line_number)),
                 });
             }
         }
         if number > 0 {
             part_numbers.push(PartNumber {
                 value: number,
-                line: line_number,
-                column: number_column
+                position: Point::from((number_column, line_number)),
             });
             number = 0;
         }
-        line_number += 1;
     }
 
     if is_part_one {
@@ -85,7 +76,7 @@ fn main() {
         for symbol in &symbols {
             if symbol.value != '*' { continue; }
             let adjacent_numbers: Vec<u32> = part_numbers.iter()
-                .filter(|x| x.is_adjacent(&symbol))
+                .filter(|x| x.is_adjacent(symbol))
                 .map(|x| x.value)
                 .collect();
             if adjacent_numbers.len() == 2 {
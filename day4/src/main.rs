@@ -1,29 +1,37 @@
+use common::bitset::BitSet;
+use common::parse::{self, ParseResult, Scanner};
+
+fn parse_numbers(scanner: &mut Scanner) -> ParseResult<Vec<usize>> {
+    scanner.ws();
+    scanner.sep_by(" ", |s| s.ws().int::<usize>())
+}
+
 #[derive(Debug, Clone)]
 struct Card {
-    id: u32,
-    winning_numbers_you_have: Vec<u32>,
+    matches: usize,
     score: u32
 }
 
 impl Card {
     fn parse(line: &str) -> Card {
-        let mut parts = line.split(": ");
-        let id = parts.next().unwrap()[4..].replace(" ", "").parse::<u32>().unwrap();
-        let mut sub_parts = parts.next().unwrap().split(" | ");
-        let winning_numbers = sub_parts.next().unwrap().split(" ")
-            .filter_map(|n| n.parse::<u32>().ok()).collect::<Vec<u32>>();
-        let numbers_you_have = sub_parts.next().unwrap().split(" ")
-            .filter_map(|n| n.parse::<u32>().ok()).collect::<Vec<u32>>();
-        let winning_numbers_you_have: Vec<u32> = winning_numbers.iter()
-            .filter(|n| numbers_you_have.contains(n)).map(|n| *n).collect();
-        let score = if winning_numbers_you_have.len() > 0 {
-            2_u32.pow(winning_numbers_you_have.len() as u32 - 1)
+        let (winning_numbers, numbers_you_have) = parse::parse_all(line, |s| {
+            s.tag("Card")?.ws().int::<u32>()?;
+            s.tag(":")?;
+            let winning_numbers = parse_numbers(s)?;
+            s.ws().tag("|")?;
+            let numbers_you_have = parse_numbers(s)?;
+            Ok((winning_numbers, numbers_you_have))
+        }).unwrap_or_else(|e| panic!("Invalid card {:?}: {}", line, e));
+        // Each winning number counts once per time it is listed, however often you have it
+        let numbers_you_have: BitSet = numbers_you_have.into_iter().collect();
+        let matches = winning_numbers.iter().filter(|n| numbers_you_have.contains(**n)).count();
+        let score = if matches > 0 {
+            2_u32.pow(matches as u32 - 1)
         } else {
             0
         };
         Card {
-            id,
-            winning_numbers_you_have,
+            matches,
             score
         }
     }
@@ -42,9 +50,10 @@ fn main() {
         let mut card_scores: Vec<u32> = vec![0; cards.len()];
         for i in (0..cards.len()).rev() {
             let card = &cards[i];
-            let mut score = card.winning_numbers_you_have.len() as u32;
-            for j in 0..score {
-                score += card_scores[1 + i + j as usize];
+            let matches = card.matches;
+            let mut score = matches as u32;
+            for j in 0..matches {
+                score += card_scores[1 + i + j];
             }
             card_scores[i] = score;
         }
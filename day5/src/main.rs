@@ -1,4 +1,6 @@
-use common::range::{Range, RangeSet};
+use common::parse::{self, Parse, ParseResult, Scanner};
+use common::range::{IntervalTree, Range, RangeSet};
+use common::AocParse;
 
 #[derive(Debug, Clone, PartialEq)]
 enum Category {
@@ -12,22 +14,43 @@ enum Category {
     Location
 }
 
-impl Category {
-    fn parse(string: &str) -> Category {
-        match string {
-            "seed" => Category::Seed,
-            "soil" => Category::Soil,
-            "fertilizer" => Category::Fertilizer,
-            "water" => Category::Water,
-            "light" => Category::Light,
-            "temperature" => Category::Temperature,
-            "humidity" => Category::Humidity,
-            "location" => Category::Location,
-            _ => panic!("Unknown category: {}", string)
-        }
+impl Parse for Category {
+    fn parse(scanner: &mut Scanner) -> ParseResult<Category> {
+        scanner.expect("a category", |s| match s.ident()? {
+            "seed" => Ok(Category::Seed),
+            "soil" => Ok(Category::Soil),
+            "fertilizer" => Ok(Category::Fertilizer),
+            "water" => Ok(Category::Water),
+            "light" => Ok(Category::Light),
+            "temperature" => Ok(Category::Temperature),
+            "humidity" => Ok(Category::Humidity),
+            "location" => Ok(Category::Location),
+            _ => s.error("a category"),
+        })
     }
 }
 
+#[derive(AocParse)]
+#[aoc(format = "seeds: {seeds:sep(' ')}")]
+struct SeedList {
+    seeds: Vec<i64>,
+}
+
+#[derive(AocParse)]
+#[aoc(format = "{from_category}-to-{to_category} map:")]
+struct MapHeader {
+    from_category: Category,
+    to_category: Category,
+}
+
+#[derive(AocParse)]
+#[aoc(format = "{destination_start} {source_start} {length}")]
+struct RangeLine {
+    destination_start: i64,
+    source_start: i64,
+    length: i64,
+}
+
 #[derive(Debug, Clone)]
 struct ValueRangeSet {
     category: Category,
@@ -39,18 +62,12 @@ struct ConversionRange {
     source_range: Range,
     modifier: i64,
 }
-impl ConversionRange {
-    fn parse(string: &str) -> ConversionRange {
-        let mut parts = string.split(" ");
-        let destination_start = parts.next().unwrap().parse::<i64>().unwrap();
-        let source_start = parts.next().unwrap().parse::<i64>().unwrap();
-        let length = parts.next().unwrap().parse::<i64>().unwrap();
+impl From<RangeLine> for ConversionRange {
+    fn from(line: RangeLine) -> ConversionRange {
         ConversionRange {
-            source_range: Range {
-                start: source_start,
-                end: source_start + length - 1
-            },
-            modifier: destination_start - source_start,
+            source_range: Range::with_length(line.source_start, line.length)
+                .unwrap_or_else(|e| panic!("Invalid source range {} +{}: {}", line.source_start, line.length, e)),
+            modifier: line.destination_start - line.source_start,
         }
     }
 }
@@ -63,30 +80,33 @@ struct Mapper {
 }
 
 impl Mapper {
-    fn map_ranges(&self, value_ranges: ValueRangeSet) -> ValueRangeSet {
+    fn build_index(&self) -> IntervalTree<i64> {
+        IntervalTree::new(self.ranges.iter()
+            .map(|r| (r.source_range.clone(), r.modifier))
+            .collect())
+    }
+
+    fn map_ranges(&self, value_ranges: ValueRangeSet, index: &IntervalTree<i64>) -> ValueRangeSet {
         // Guard against mapping from the wrong category
         if value_ranges.category != self.from_category {
             panic!("Cannot map from {:?} to {:?}, expected {:?}",
                    value_ranges.category, self.to_category, self.from_category);
         }
 
-        let mut ranges_to_convert = value_ranges.ranges.clone();
         let mut converted_ranges: Vec<Range> = Vec::new();
-        for conversion_range in &self.ranges {
-            let overlaps =
-                ranges_to_convert.find_overlaps_with_range(&conversion_range.source_range);
-            for overlap in overlaps.ranges {
+        for value_range in &value_ranges.ranges.ranges {
+            let mut unmapped_ranges = RangeSet { ranges: vec![value_range.clone()] };
+            for (source_range, modifier) in index.find_overlapping(value_range) {
+                let overlap = value_range.find_overlap(source_range).unwrap();
                 converted_ranges.push(Range {
-                    start: overlap.start + conversion_range.modifier,
-                    end: overlap.end + conversion_range.modifier,
+                    start: overlap.start + modifier,
+                    end: overlap.end + modifier,
                 });
+                unmapped_ranges = unmapped_ranges.subtract_range(source_range);
             }
-            ranges_to_convert = ranges_to_convert.subtract_range(&conversion_range.source_range);
+            converted_ranges.append(&mut unmapped_ranges.ranges);
         }
 
-        let mut unmapped_ranges = ranges_to_convert.ranges.clone();
-        converted_ranges.append(&mut unmapped_ranges);
-
         ValueRangeSet {
             category: self.to_category.clone(),
             ranges: RangeSet {
@@ -100,6 +120,10 @@ impl Mapper {
     }
 }
 
+fn parse_line<T: Parse>(line: &str) -> T {
+    parse::parse_line(line).unwrap_or_else(|e| panic!("Invalid line {:?}: {}", line, e))
+}
+
 fn main() {
     let is_part_one = common::is_part_one();
     let input_file_path = common::get_input_file_path();
@@ -108,31 +132,25 @@ fn main() {
     let mut current_mapper: Option<Mapper> = None;
     for line in common::read_file_line_by_line(&input_file_path) {
         if line.starts_with("seeds: ") {
-            seeds = line.replace("seeds: ", "").split(" ")
-                .filter_map(|n| n.parse::<i64>().ok()).collect();
+            seeds = parse_line::<SeedList>(&line).seeds;
         } else if line.ends_with(" map:") {
-            let parts: Vec<String> = line.replace(" map:", "")
-                .split("-to-")
-                .map(|s| s.to_string())
-                .collect();
-            let from_category = Category::parse(&parts[0]);
-            let to_category = Category::parse(&parts[1]);
+            let header: MapHeader = parse_line(&line);
             current_mapper = Some(Mapper {
-                from_category,
-                to_category,
+                from_category: header.from_category,
+                to_category: header.to_category,
                 ranges: Vec::new()
             });
-        } else if line.len() > 0 {
-            let range = ConversionRange::parse(&line);
-            current_mapper.as_mut().unwrap().ranges.push(range);
+        } else if !line.is_empty() {
+            let range: RangeLine = parse_line(&line);
+            current_mapper.as_mut().unwrap().ranges.push(range.into());
         } else if current_mapper.is_some() {
             mappers.push(current_mapper.unwrap().clone());
             current_mapper = None;
         }
     }
 
-    if current_mapper.is_some() {
-        mappers.push(current_mapper.unwrap().clone());
+    if let Some(mapper) = current_mapper {
+        mappers.push(mapper);
     }
 
     let mut values = if is_part_one {
@@ -148,16 +166,18 @@ fn main() {
             ranges: RangeSet {
                 ranges: seeds
                     .chunks(2)
-                    .map(|x| Range { start: x[0], end: x[0] + x[1] - 1 })
+                    .map(|x| Range::with_length(x[0], x[1])
+                        .unwrap_or_else(|e| panic!("Invalid seed range {} +{}: {}", x[0], x[1], e)))
                     .collect()
             }
         }
     };
 
+    let indexes: Vec<IntervalTree<i64>> = mappers.iter().map(|m| m.build_index()).collect();
     while values.category != Category::Location {
-        for mapper in &mappers {
+        for (mapper, index) in mappers.iter().zip(indexes.iter()) {
             if mapper.can_map(&values) {
-                values = mapper.map_ranges(values);
+                values = mapper.map_ranges(values, index);
                 break;
             }
         }
@@ -1,12 +1,11 @@
-fn get_min_hold_time(time: i64, target_distance: i64) -> i64 {
-    let time_f = time as f64;
-    let target_distance_f = target_distance as f64;
-    f64::ceil((time_f - f64::sqrt(time_f.powf(2.0) - 4.0 * target_distance_f)) / 2.0) as i64
-}
-fn get_max_hold_time(time: i64, target_distance: i64) -> i64 {
-    let time_f = time as f64;
-    let target_distance_f = target_distance as f64;
-    f64::floor((time_f + f64::sqrt(time_f.powf(2.0) - 4.0 * target_distance_f)) / 2.0) as i64
+use common::{math, parse};
+
+// Holding for h ms out of t travels h * (t - h), so the winning holds are the integers where
+// h^2 - t * h + target <= 0
+fn get_hold_time_range(time: i64, target_distance: i64) -> (i64, i64) {
+    let (min, max) = math::quadratic_nonpositive_range(1, -(time as i128), target_distance as i128)
+        .unwrap_or_else(|| panic!("No hold time reaches {} in {}ms", target_distance, time));
+    (min as i64, max as i64)
 }
 
 #[derive(Debug)]
@@ -15,39 +14,44 @@ struct Race {
     target_distance: i64,
 }
 impl Race {
+    // The raw digit runs, so joining them keeps any leading zeros
+    fn parse_digits<'a>(line: &'a str, label: &str) -> Vec<&'a str> {
+        parse::parse_all(line, |s| {
+            s.tag(label)?.tag(":")?.ws();
+            s.sep_by(" ", |s| {
+                let digits = s.ws().take_while(|c| c.is_ascii_digit());
+                if digits.is_empty() { s.error("digits") } else { Ok(digits) }
+            })
+        }).unwrap_or_else(|e| panic!("Invalid {} line {:?}: {}", label, line, e))
+    }
+
+    fn parse_numbers(line: &str, label: &str) -> Vec<i64> {
+        Race::parse_digits(line, label).iter().map(|digits| digits.parse::<i64>().unwrap()).collect()
+    }
+
     fn parse_all(times_line: &str, distances_line: &str) -> Vec<Race> {
-        let mut times = times_line
-            .chars().skip(5).skip_while(|c| c.is_whitespace()).collect::<String>()
-            .split_whitespace().map(|x| x.parse::<i64>().unwrap()).collect::<Vec<i64>>();
-        let mut target_distances = distances_line
-            .chars().skip(9).skip_while(|c| c.is_whitespace()).collect::<String>()
-            .split_whitespace().map(|x| x.parse::<i64>().unwrap() + 1).collect::<Vec<i64>>();
+        let times = Race::parse_numbers(times_line, "Time");
+        let target_distances = Race::parse_numbers(distances_line, "Distance");
 
         if times.len() != target_distances.len() {
             panic!("Times and target distances must be the same length");
         }
 
-        let mut races: Vec<Race> = Vec::new();
-        for i in 0..times.len() {
-            races.push(Race {
-                time: times[i],
-                target_distance: target_distances[i],
-            });
-        }
-        races
+        times.into_iter().zip(target_distances)
+            .map(|(time, distance)| Race { time, target_distance: distance + 1 })
+            .collect()
     }
 
     fn parse_single(times_line: &str, distances_line: &str) -> Race {
-        let mut time = times_line
-            .chars().skip(5).collect::<String>()
-            .replace(" ", "").parse::<i64>().unwrap();
-        let mut target_distance = distances_line
-            .chars().skip(9).collect::<String>()
-            .replace(" ", "").parse::<i64>().unwrap();
+        // The spaces between numbers are kerning, so the digits form a single number
+        let join = |digits: Vec<&str>| digits.concat().parse::<i64>().unwrap();
+        let time = join(Race::parse_digits(times_line, "Time"));
+        let target_distance = join(Race::parse_digits(distances_line, "Distance"));
 
+        // Only beating the record wins, so the target is one past it as in part 1
         Race {
             time,
-            target_distance,
+            target_distance: target_distance + 1,
         }
     }
 }
@@ -62,23 +66,19 @@ fn main() {
 
     if is_part_one {
         let races = Race::parse_all(&lines[0], &lines[1]);
-        let mut i = 1;
         let mut result = 1;
-        for race in races {
-            let min_hold_time = get_min_hold_time(race.time, race.target_distance);
-            let max_hold_time = get_max_hold_time(race.time, race.target_distance);
+        for (i, race) in (1..).zip(races) {
+            let (min_hold_time, max_hold_time) = get_hold_time_range(race.time, race.target_distance);
             let options = max_hold_time - min_hold_time + 1;
             println!("For race {} you have to hold for {}-{}ms ({} options)",
                      i, min_hold_time, max_hold_time, options
             );
             result *= options;
-            i += 1;
         }
         println!("Result: {}", result);
     } else {
         let race = Race::parse_single(&lines[0], &lines[1]);
-        let min_hold_time = get_min_hold_time(race.time, race.target_distance);
-        let max_hold_time = get_max_hold_time(race.time, race.target_distance);
+        let (min_hold_time, max_hold_time) = get_hold_time_range(race.time, race.target_distance);
         let options = max_hold_time - min_hold_time + 1;
         println!("To win the race you have to hold for {}-{}ms ({} options)",
                  min_hold_time, max_hold_time, options
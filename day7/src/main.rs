@@ -1,20 +1,38 @@
 use std::cmp::Ordering;
 
-#[derive(Debug)]
+use common::counter::Counter;
+use common::TileChar;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, TileChar)]
 enum Card {
+    // Jokers are read as J in part two, so they render as * to keep J meaning Jack
+    #[tile(render = '*')]
     Joker,
+    #[tile('2')]
     Two,
+    #[tile('3')]
     Three,
+    #[tile('4')]
     Four,
+    #[tile('5')]
     Five,
+    #[tile('6')]
     Six,
+    #[tile('7')]
     Seven,
+    #[tile('8')]
     Eight,
+    #[tile('9')]
     Nine,
+    #[tile('T')]
     Ten,
+    #[tile('J')]
     Jack,
+    #[tile('Q')]
     Queen,
+    #[tile('K')]
     King,
+    #[tile('A')]
     Ace,
 }
 impl Card {
@@ -38,21 +56,10 @@ impl Card {
     }
 
     fn parse(c: char, is_part_one: bool) -> Card {
-        match c {
-            '2' => Card::Two,
-            '3' => Card::Three,
-            '4' => Card::Four,
-            '5' => Card::Five,
-            '6' => Card::Six,
-            '7' => Card::Seven,
-            '8' => Card::Eight,
-            '9' => Card::Nine,
-            'T' => Card::Ten,
-            'J' => if is_part_one { Card::Jack } else { Card::Joker },
-            'Q' => Card::Queen,
-            'K' => Card::King,
-            'A' => Card::Ace,
-            _ => panic!("Invalid card symbol: {}", c),
+        match Card::try_from(c) {
+            Ok(Card::Jack) if !is_part_one => Card::Joker,
+            Ok(card) => card,
+            Err(c) => panic!("Invalid card symbol: {}", c),
         }
     }
 }
@@ -80,31 +87,25 @@ impl HandType {
         }
     }
 
+    // Jokers always do best by joining the largest group
     fn identify(cards: &[Card; 5]) -> HandType {
-        let mut jokers = 0;
-        let mut counts = [0; 13];
-        for card in cards.iter() {
-            match card {
-                Card::Joker => jokers += 1,
-                _ => counts[card.value() as usize] += 1,
-            }
+        let mut counter: Counter<Card> = cards.iter().copied().collect();
+        let jokers = counter.take(&Card::Joker);
+        let mut signature = counter.signature();
+        match signature.first_mut() {
+            Some(largest) => *largest += jokers,
+            None => signature.push(jokers),
         }
-        let pairs = counts.iter().filter(|count| **count == 2).count();
 
-        if counts.iter().any(|count| *count == 5 - jokers) {
-            return HandType::FiveOfAKind;
-        } else if counts.iter().any(|count| *count == 4 - jokers) {
-            return HandType::FourOfAKind;
-        } else if counts.iter().filter(|count| **count > 0).count() == 2 {
-            return HandType::FullHouse;
-        } else if counts.iter().any(|count| *count == 3 - jokers) {
-            return HandType::ThreeOfAKind;
-        } else if pairs == 2 - jokers {
-            return HandType::TwoPairs;
-        } else if counts.iter().any(|count| *count == 2 - jokers) {
-            return HandType::OnePair;
+        match signature.as_slice() {
+            [5] => HandType::FiveOfAKind,
+            [4, 1] => HandType::FourOfAKind,
+            [3, 2] => HandType::FullHouse,
+            [3, 1, 1] => HandType::ThreeOfAKind,
+            [2, 2, 1] => HandType::TwoPairs,
+            [2, 1, 1, 1] => HandType::OnePair,
+            _ => HandType::HighCard,
         }
-        HandType::HighCard
     }
 }
 
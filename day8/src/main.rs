@@ -1,16 +1,7 @@
-use std::collections::{HashMap};
-
-fn gcd(a: usize, b: usize) -> usize {
-    if b == 0 {
-        a
-    } else {
-        gcd(b, a % b)
-    }
-}
-
-fn lcm(a: usize, b: usize) -> usize {
-    a * b / gcd(a, b)
-}
+use common::cycle;
+use common::intern::Interner;
+use common::math;
+use common::parse;
 
 #[derive(Copy, Clone, Debug)]
 enum Direction { Left, Right }
@@ -24,48 +15,81 @@ impl Direction {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 struct Node {
-    label: String,
-    id: usize,
-
-    left_label: String,
-    left_id: usize,
+    left_id: u32,
+    right_id: u32,
+}
 
-    right_label: String,
-    right_id: usize,
+struct ZCycle {
+    // Steps before the path starts looping that land on a Z node
+    early_hits: Vec<usize>,
+    // Steps within the first pass through the cycle that land on a Z node
+    hits: Vec<usize>,
+    start: usize,
+    length: usize,
+}
+impl ZCycle {
+    fn is_z_at(&self, step: usize) -> bool {
+        if step < self.start {
+            self.early_hits.binary_search(&step).is_ok()
+        } else {
+            self.hits.binary_search(&(self.start + (step - self.start) % self.length)).is_ok()
+        }
+    }
 }
 
-struct HistoryEntry {
-    node_id: usize,
-    steps: usize,
-    direction_offset: usize,
+// Walks until the (node, direction offset) state repeats, which is where the path starts looping
+fn find_z_cycle(start_node_id: u32, nodes: &[Node], labels: &Interner, directions: &[Direction]) -> ZCycle {
+    // The state is the node plus the offset into the directions, which only repeats once both do
+    let cycle = cycle::detect_with((start_node_id, 0), |(node_id, offset)| {
+        let next_node_id = match directions[*offset] {
+            Direction::Left => nodes[*node_id as usize].left_id,
+            Direction::Right => nodes[*node_id as usize].right_id,
+        };
+        (next_node_id, (offset + 1) % directions.len())
+    });
+    let is_z = |step: &usize| labels.resolve(cycle.history()[*step].0).ends_with('Z');
+    let early_hits = (0..cycle.start).filter(is_z).collect();
+    let hits = (cycle.start..cycle.start + cycle.length).filter(is_z).collect();
+    ZCycle { early_hits, hits, start: cycle.start, length: cycle.length }
 }
 
-fn find_offsets(start_node_id: usize, nodes: &Vec<Node>, directions: Vec<Direction>) {
-    let mut history: HashMap<(usize, usize), usize> = HashMap::new();
-    let mut current_node_id = start_node_id;
-    let mut steps = 0;
+// Before every path is looping the steps are checked one by one. After that each path is at a
+// Z node at hit + k * length for every hit in its cycle, so try every combination of hits and
+// keep the earliest step that all paths agree on.
+fn find_alignment(cycles: &[ZCycle]) -> Option<i128> {
+    let latest_start = cycles.iter().map(|c| c.start).max()?;
+    if let Some(step) = (0..latest_start).find(|step| cycles.iter().all(|c| c.is_z_at(*step))) {
+        return Some(step as i128);
+    }
+    // A path whose loop never reaches a Z node can only align before it starts looping
+    if cycles.iter().any(|c| c.hits.is_empty()) {
+        return None;
+    }
+    let latest_start = latest_start as i128;
+    let mut best: Option<i128> = None;
+    let mut choices = vec![0; cycles.len()];
     loop {
-        let direction_offset = steps % directions.len();
-        let direction = directions[direction_offset];
-        let next_node_id = match direction {
-            Direction::Left => nodes[current_node_id].left_id,
-            Direction::Right => nodes[current_node_id].right_id,
-        };
-        if nodes[next_node_id].label.ends_with("Z") {
-            if history.contains_key(&(direction_offset, next_node_id)) {
-                println!("Found a loop from {} - {} steps",
-                         history.get(&(direction_offset, next_node_id)).unwrap(),
-                         steps
-                );
-                break;
+        let congruences = cycles.iter().zip(choices.iter())
+            .map(|(cycle, choice)| (cycle.hits[*choice] as i128, cycle.length as i128))
+            .collect::<Vec<(i128, i128)>>();
+        if let Some((mut step, modulus)) = math::crt(&congruences) {
+            if step < latest_start {
+                step += (latest_start - step + modulus - 1) / modulus * modulus;
             }
-        } else {
-            history.insert((direction_offset, next_node_id), steps);
-            println!("Updated History: {:?}", history);
+            best = Some(best.map_or(step, |b| b.min(step)));
+        }
+
+        let mut i = 0;
+        while i < choices.len() && choices[i] + 1 == cycles[i].hits.len() {
+            choices[i] = 0;
+            i += 1;
         }
-        steps += 1;
+        if i == choices.len() {
+            return best;
+        }
+        choices[i] += 1;
     }
 }
 
@@ -74,70 +98,57 @@ fn main() {
     let input_file_path = common::get_input_file_path();
     let lines = common::read_file_line_by_line(&input_file_path);
 
-    let directions = lines[0].chars().map(|c| Direction::parse(c)).collect::<Vec<Direction>>();
-    let mut nodes: Vec<Node> = Vec::new();
-    let mut node_mapping: HashMap<String, usize> = HashMap::new();
-
-    let mut next_id = 0;
+    let directions = lines[0].chars().map(Direction::parse).collect::<Vec<Direction>>();
+    // Nodes are referenced before their own line, so interning fixes their ids up front
+    let mut labels = Interner::new();
+    let mut defined_nodes: Vec<Option<Node>> = Vec::new();
     for line in lines[2..].iter() {
+        let (label, left_label, right_label) = parse::parse_all(line, |s| {
+            let label = s.ident()?;
+            s.ws().tag("=")?.ws().tag("(")?;
+            let left = s.ident()?;
+            s.tag(",")?.ws();
+            let right = s.ident()?;
+            s.tag(")")?;
+            Ok((label, left, right))
+        }).unwrap_or_else(|e| panic!("Invalid node {:?}: {}", line, e));
+        let id = labels.intern(label) as usize;
         let node = Node {
-            label: line[0..3].to_string(),
-            id: next_id,
-
-            left_label: line[7..10].to_string(),
-            left_id: 0,
-
-            right_label: line[12..15].to_string(),
-            right_id: 0,
+            left_id: labels.intern(left_label),
+            right_id: labels.intern(right_label),
         };
-        node_mapping.insert(node.label.clone(), next_id);
-        nodes.push(node);
-        next_id += 1;
-    }
-    for node in nodes.iter_mut() {
-        node.left_id = *node_mapping.get(&node.left_label).unwrap();
-        node.right_id = *node_mapping.get(&node.right_label).unwrap();
+        defined_nodes.resize(labels.len(), None);
+        if defined_nodes[id].is_some() {
+            panic!("Node {} is defined twice", label);
+        }
+        defined_nodes[id] = Some(node);
     }
+    let nodes = defined_nodes.iter().enumerate()
+        .map(|(id, node)| node.unwrap_or_else(|| panic!("Node {} is never defined", labels.resolve(id as u32))))
+        .collect::<Vec<Node>>();
 
     if is_part_one {
         let mut steps: usize = 0;
-        let mut current_node_id = *node_mapping.get("AAA").unwrap();
-        while current_node_id != *node_mapping.get("ZZZ").unwrap() {
+        let mut current_node_id = labels.get("AAA").expect("No AAA node");
+        let target_node_id = labels.get("ZZZ").expect("No ZZZ node");
+        while current_node_id != target_node_id {
             let direction = directions[steps % directions.len()];
             current_node_id = match direction {
-                Direction::Left => nodes[current_node_id].left_id,
-                Direction::Right => nodes[current_node_id].right_id,
+                Direction::Left => nodes[current_node_id as usize].left_id,
+                Direction::Right => nodes[current_node_id as usize].right_id,
             };
             steps += 1;
         }
 
         println!("{:#?}", steps);
     } else {
-        let mut starting_node_ids: Vec<usize> = Vec::new();
-        for node in nodes.iter() {
-            if node.label.ends_with("A") {
-                starting_node_ids.push(node.id);
-            }
-        }
-        let mut path_lengths: Vec<usize> = Vec::new();
-        for starting_node_id in starting_node_ids.iter() {
-            let mut steps: usize = 0;
-            let mut current_node_id = starting_node_id;
-            while !nodes[*current_node_id].label.ends_with("Z") {
-                let direction = directions[steps % directions.len()];
-                current_node_id = match direction {
-                    Direction::Left => &nodes[*current_node_id].left_id,
-                    Direction::Right => &nodes[*current_node_id].right_id,
-                };
-                steps += 1;
-            }
-            path_lengths.push(steps);
-        }
-        // get lcm of all path lengths
-        let mut result = path_lengths[0];
-        for i in 1..path_lengths.len() {
-            result = lcm(result, path_lengths[i]);
+        let cycles = labels.iter()
+            .filter(|(_, label)| label.ends_with('A'))
+            .map(|(id, _)| find_z_cycle(id, &nodes, &labels, &directions))
+            .collect::<Vec<ZCycle>>();
+        match find_alignment(&cycles) {
+            Some(result) => println!("{:#?}", result),
+            None => println!("Paths never align"),
         }
-        println!("{:#?}", result);
     }
 }
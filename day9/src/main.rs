@@ -1,3 +1,5 @@
+use common::poly::{self, BigInt};
+
 type History = Vec<i64>;
 
 fn parse_history(line: &str) -> History {
@@ -6,21 +8,16 @@ fn parse_history(line: &str) -> History {
         .collect()
 }
 
-fn get_history_derivative(history: &History) -> History {
-    let mut derivative = Vec::new();
-    for i in 1..history.len() {
-        derivative.push(history[i] - history[i - 1]);
-    }
-    derivative
-}
-
-fn is_all_zeros(history: &History) -> bool {
-    for i in history {
-        if *i != 0 {
-            return false;
-        }
-    }
-    true
+// Leaves at least one row of differences to confirm the all-zero row was actually reached.
+// One or two values are too few to confirm anything, so they are read as a constant or a line.
+fn extrapolate(history: &History, is_part_one: bool) -> BigInt {
+    let max_degree = if history.len() <= 2 { history.len().saturating_sub(1) } else { history.len() - 2 };
+    let value = if is_part_one {
+        poly::extrapolate_forward(history, 1, max_degree)
+    } else {
+        poly::extrapolate_backward(history, 1, max_degree)
+    };
+    value.unwrap_or_else(|e| panic!("Cannot extrapolate {:?}: {}", history, e))
 }
 
 fn main() {
@@ -29,27 +26,9 @@ fn main() {
     let lines = common::read_file_line_by_line(&input_file_path);
     let histories = lines.iter().map(|line| parse_history(line)).collect::<Vec<History>>();
 
-    let mut result: i64 = 0;
-    for history in histories {
-        let mut derivatives: Vec<History> = Vec::new();
-        derivatives.push(get_history_derivative(&history));
-        while !is_all_zeros(derivatives.last().unwrap()) {
-            derivatives.push(get_history_derivative(derivatives.last().unwrap()));
-        }
-
-        derivatives.reverse();
-        let mut last_value: i64 = 0;
-        for derivative in derivatives {
-            if is_part_one {
-                last_value = last_value + derivative.last().unwrap();
-            } else {
-                last_value = derivative.first().unwrap() - last_value;
-            }
-        }
-        last_value = history.first().unwrap() - last_value;
-
-        result += last_value;
-    }
+    let result: BigInt = histories.iter()
+        .map(|history| extrapolate(history, is_part_one))
+        .sum();
 
     println!("Result: {}", result);
 }